@@ -0,0 +1,51 @@
+//! Measures dictionary load time and peak memory.
+//!
+//! ```sh
+//! cargo run --release --example load_dictionary -- ./polish.out.br
+//! ```
+//!
+//! The printed fingerprint doesn't depend on hash map iteration order, so it can be
+//! compared across runs and thread counts to check that loading is deterministic.
+
+use lemmatizer::{Dictionary, Error};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::Instant;
+
+fn main() -> Result<(), Error> {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "./polish.out.br".to_string());
+
+    let start = Instant::now();
+    let dictionary = Dictionary::load(&path)?;
+    let elapsed = start.elapsed();
+
+    let mut fingerprint = 0u64;
+    dictionary.for_each_form(|form, entries| {
+        let mut hasher = DefaultHasher::new();
+        form.hash(&mut hasher);
+        entries.hash(&mut hasher);
+        fingerprint = fingerprint.wrapping_add(hasher.finish());
+    });
+
+    println!("load time\t{:.2?}", elapsed);
+    println!(
+        "peak memory\t{}",
+        peak_memory().unwrap_or_else(|| "n/a".to_string())
+    );
+    println!("forms\t{}", dictionary.len());
+    println!("lemmas\t{}", dictionary.lemma_count());
+    println!("fingerprint\t{:016x}", fingerprint);
+
+    Ok(())
+}
+
+/// Peak resident set size, as reported by Linux.
+fn peak_memory() -> Option<String> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find(|line| line.starts_with("VmHWM:"))
+        .map(|line| line["VmHWM:".len()..].trim().to_string())
+}
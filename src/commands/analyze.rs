@@ -0,0 +1,48 @@
+use lemmatizer::Error;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use super::{
+    analyze_files, expand_globs, report_missing, write_output, DocumentArgs, LemmatizerArgs,
+};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Glob patterns of the Markdown files to analyze
+    #[arg(required = true)]
+    inputs: Vec<String>,
+
+    #[command(flatten)]
+    lemmatizer: LemmatizerArgs,
+
+    #[command(flatten)]
+    document: DocumentArgs,
+
+    /// Write words missing from the dictionary to this TSV file instead of
+    /// printing the most frequent ones
+    #[arg(long)]
+    oov_report: Option<PathBuf>,
+
+    /// Where to write the JSON, stdout by default
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+pub fn run(args: Args) -> Result<(), Error> {
+    let files = expand_globs(&args.inputs)?;
+    let options = args.document.options();
+    let lemmatizer = args.lemmatizer.load_for(&files, &options)?;
+    let analyzed_files = analyze_files(&files, &lemmatizer, &options);
+    report_missing(&analyzed_files, &lemmatizer, args.oov_report.as_ref())?;
+
+    let counters = analyzed_files
+        .iter()
+        .map(|document| {
+            let counter = document.counter.iter().collect::<BTreeMap<_, _>>();
+            (&document.permalink, counter)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let json = serde_json::to_string_pretty(&counters)?;
+    write_output(args.output.as_ref(), &json)
+}
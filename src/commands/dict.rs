@@ -0,0 +1,151 @@
+use clap::Subcommand;
+use lemmatizer::{Error, Guesser, Lemmatizer};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+use super::LemmatizerArgs;
+
+#[derive(clap::Args)]
+pub struct Args {
+    #[command(flatten)]
+    lemmatizer: LemmatizerArgs,
+
+    #[command(subcommand)]
+    command: DictCommand,
+}
+
+#[derive(Subcommand)]
+enum DictCommand {
+    /// Prints the chosen lemma and all candidate readings of each word form
+    Lookup {
+        #[arg(required = true)]
+        forms: Vec<String>,
+    },
+    /// Lists all known forms of a lemma
+    Forms { lemma: String },
+    /// Prints dictionary statistics
+    Stats,
+    /// Writes the dictionary in the compiled, memory-mapped format
+    Compile { output: PathBuf },
+    /// Learns suffix rules for guessing lemmas of unknown words
+    TrainGuesser { output: PathBuf },
+    /// Suggests spellings with diacritics of each word, most likely first
+    Restore {
+        #[arg(required = true)]
+        words: Vec<String>,
+    },
+    /// Restores diacritics of words missing from the dictionary in text files,
+    /// or stdin when none are given
+    RestoreText { files: Vec<PathBuf> },
+}
+
+pub fn run(args: Args) -> Result<(), Error> {
+    match args.command {
+        DictCommand::Compile { output } => {
+            let dictionary = args.lemmatizer.load_dictionary()?;
+            eprintln!("Writing {}…", output.display());
+            dictionary.compile(&output)?;
+        }
+        DictCommand::TrainGuesser { output } => {
+            let dictionary = args.lemmatizer.load_dictionary()?;
+            eprintln!("Training guesser…");
+            let guesser = Guesser::train(&dictionary);
+            eprintln!("Writing {} endings to {}…", guesser.len(), output.display());
+            guesser.save(&output)?;
+        }
+        DictCommand::Lookup { forms } => {
+            let lemmatizer = args.lemmatizer.load()?;
+            for form in forms {
+                let form = spelling(&form, |form| !lemmatizer.candidates(form).is_empty());
+                match (lemmatizer.lemmatize(&form), lemmatizer.guess(&form)) {
+                    (Some(lemma), _) => println!("{}\t{}", form, lemma),
+                    (None, Some(guess)) => println!(
+                        "{}\t{}\t(guessed, confidence {:.2})",
+                        form, guess.lemma, guess.confidence
+                    ),
+                    (None, None) => println!("{}\t-", form),
+                }
+                for entry in lemmatizer.candidates(&form) {
+                    println!("\t{}\t{}", entry.lemma, entry.raw_tags);
+                }
+            }
+        }
+        DictCommand::Forms { lemma } => {
+            let lemmatizer = args.lemmatizer.load()?;
+            let dictionary = lemmatizer.dictionary();
+            let lemma = spelling(&lemma, |lemma| dictionary.paradigm_size(lemma) > 0);
+            let mut forms = Vec::new();
+            dictionary.for_each_form(|form, entries| {
+                if entries.iter().any(|entry| entry.lemma == lemma) {
+                    forms.push(form.to_string());
+                }
+            });
+            forms.sort_unstable();
+            for form in forms {
+                println!("{}", form);
+            }
+        }
+        DictCommand::Stats => {
+            let lemmatizer = args.lemmatizer.load()?;
+            let dictionary = lemmatizer.dictionary();
+            let mut ambiguous = 0;
+            dictionary.for_each_form(|_, entries| {
+                if entries.iter().any(|entry| entry.lemma != entries[0].lemma) {
+                    ambiguous += 1;
+                }
+            });
+            println!("forms\t{}", dictionary.len());
+            println!("lemmas\t{}", dictionary.lemma_count());
+            println!("ambiguous forms\t{}", ambiguous);
+            println!("stopwords\t{}", lemmatizer.stopwords().len());
+        }
+        DictCommand::Restore { words } => {
+            let lemmatizer = load_with_folding(&args.lemmatizer)?;
+            for word in words {
+                let word = word.to_lowercase();
+                println!("{}\t{}", word, lemmatizer.restore(&word).join(" "));
+            }
+        }
+        DictCommand::RestoreText { files } => {
+            let lemmatizer = load_with_folding(&args.lemmatizer)?;
+            let stdout = std::io::stdout();
+            let mut out = stdout.lock();
+            let mut restore_lines = |reader: &mut dyn BufRead| -> Result<(), Error> {
+                for line in reader.lines() {
+                    writeln!(out, "{}", lemmatizer.restore_text(&line?))?;
+                }
+                Ok(())
+            };
+            if files.is_empty() {
+                restore_lines(&mut std::io::stdin().lock())?;
+            }
+            for path in &files {
+                let file =
+                    std::fs::File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+                restore_lines(&mut std::io::BufReader::new(file))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// `word` as written when `known`, lowercased otherwise, so capitalized entries
+/// such as `Polska` can be looked up too.
+fn spelling(word: &str, known: impl Fn(&str) -> bool) -> String {
+    if known(word) {
+        word.to_string()
+    } else {
+        word.to_lowercase()
+    }
+}
+
+/// Loads the lemmatizer with diacritics folding, which restoring needs.
+fn load_with_folding(args: &LemmatizerArgs) -> Result<Lemmatizer, Error> {
+    let lemmatizer = args.load()?;
+    Ok(if args.fold_diacritics {
+        lemmatizer
+    } else {
+        lemmatizer.with_diacritics_folding()
+    })
+}
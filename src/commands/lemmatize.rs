@@ -0,0 +1,80 @@
+use lemmatizer::Error;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+use super::LemmatizerArgs;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Text files to lemmatize, stdin when none are given
+    files: Vec<PathBuf>,
+
+    #[command(flatten)]
+    lemmatizer: LemmatizerArgs,
+
+    /// Leave out stopwords
+    #[arg(long)]
+    skip_stopwords: bool,
+
+    /// Print morphosyntactic tags after each lemma, as `lemma/tag+tag`
+    #[arg(long)]
+    tags: bool,
+}
+
+pub fn run(args: Args) -> Result<(), Error> {
+    let lemmatizer = args.lemmatizer.load()?;
+
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+
+    let mut lemmatize_lines = |reader: &mut dyn BufRead| -> Result<(), Error> {
+        for line in reader.lines() {
+            let line = line?;
+            let words = lemmatizer
+                .tokenizer()
+                .tokenize(&line)
+                .into_iter()
+                .filter(|token| token.kind.is_word())
+                .map(|token| lemmatizer.lookup_form(&token))
+                .collect::<Vec<String>>();
+            let lemmas = words
+                .iter()
+                .filter(|word| {
+                    !(args.skip_stopwords && lemmatizer.is_stopword(&word.to_lowercase()))
+                })
+                .map(|word| {
+                    if let Some(lemma) = lemmatizer.compound_lemma(word) {
+                        return lemma;
+                    }
+                    let token = lemmatizer.token(word);
+                    let guess = token.guess.as_ref().map(|guess| guess.lemma.as_str());
+                    let lemma = token.lemma.or(guess).unwrap_or(word);
+                    if args.tags && !token.tags.is_empty() {
+                        let tags = token
+                            .tags
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>();
+                        format!("{}/{}", lemma, tags.join("+"))
+                    } else {
+                        lemma.to_string()
+                    }
+                })
+                .collect::<Vec<String>>();
+            writeln!(out, "{}", lemmas.join(" "))?;
+        }
+        Ok(())
+    };
+
+    if args.files.is_empty() {
+        lemmatize_lines(&mut std::io::stdin().lock())?;
+    } else {
+        for path in &args.files {
+            let file =
+                std::fs::File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            lemmatize_lines(&mut std::io::BufReader::new(file))?;
+        }
+    }
+
+    Ok(())
+}
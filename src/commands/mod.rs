@@ -0,0 +1,364 @@
+use clap::{Args, ValueEnum};
+use lemmatizer::document::{FallbackId, Field, FieldWeights};
+use lemmatizer::tokenizer::{Rules, Tokenizer};
+use lemmatizer::{
+    analyze_path, document, lemmatizer::build_stopwords, oov, Casing, Compounds, Dictionary,
+    Disambiguation, Document, Error, Guesser, Lemmatizer, PartOfSpeech,
+};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub mod analyze;
+pub mod dict;
+pub mod lemmatize;
+pub mod related;
+
+/// Options shared by every subcommand that needs the dictionary.
+#[derive(Args)]
+pub struct LemmatizerArgs {
+    /// Dictionary compiled with `dict compile`, or a Brotli-compressed `lemma;form[;tags]` one
+    #[arg(short, long, default_value = "./polish.out.br")]
+    pub dictionary: PathBuf,
+
+    /// File with one stopword per line
+    #[arg(short, long, default_value = "./stopwords.txt")]
+    pub stopwords: PathBuf,
+
+    /// How to choose between several lemmas of an ambiguous word form
+    #[arg(long, value_enum, default_value_t = DisambiguationArg::Corpus)]
+    pub disambiguation: DisambiguationArg,
+
+    /// Only count words with these parts of speech, e.g. `subst,adj`
+    #[arg(long = "pos", value_delimiter = ',')]
+    pub parts_of_speech: Vec<PartOfSpeech>,
+
+    /// Suffix rules written by `dict train-guesser`, used to lemmatize unknown words;
+    /// unknown words are kept as they are without it
+    #[arg(long)]
+    pub guesser: Option<PathBuf>,
+
+    /// Minimum confidence of a guessed lemma, between 0 and 1
+    #[arg(long, default_value_t = 0.5)]
+    pub min_confidence: f32,
+
+    /// Extra `lemma;form`, `.csv` or `.toml` dictionary whose forms replace those of
+    /// the main dictionary; later files take precedence
+    #[arg(short, long = "user-dictionary")]
+    pub user_dictionaries: Vec<PathBuf>,
+
+    /// How hyphenated compounds missing from the dictionary are lemmatized
+    #[arg(long, value_enum, default_value_t = CompoundsArg::Parts)]
+    pub compounds: CompoundsArg,
+
+    /// Look up words missing from the dictionary by their spelling without
+    /// diacritics, e.g. `zolw` as `żółw`
+    #[arg(long)]
+    pub fold_diacritics: bool,
+
+    /// Split these kinds of tokens into their parts instead of keeping them whole
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub split: Vec<SplitArg>,
+
+    /// Whether capitalized words are looked up as written, e.g. `Polska` as the
+    /// country rather than the adjective `polski`
+    #[arg(long, value_enum, default_value_t = CasingArg::Ignore)]
+    pub casing: CasingArg,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum CasingArg {
+    /// Lowercase every word before looking it up
+    Ignore,
+    /// Look up words capitalized mid-sentence as written first, counting their
+    /// lemmas lowercased
+    Lookup,
+    /// Like `lookup`, counting proper nouns as written apart from common words
+    ProperNouns,
+}
+
+impl From<CasingArg> for Casing {
+    fn from(arg: CasingArg) -> Self {
+        match arg {
+            CasingArg::Ignore => Casing::Ignore,
+            CasingArg::Lookup => Casing::Lookup,
+            CasingArg::ProperNouns => Casing::ProperNouns,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum CompoundsArg {
+    /// Lemmatize and count every part on its own
+    Parts,
+    /// Look up the compound as a whole only
+    Whole,
+    /// Lemmatize every part and join them back with hyphens
+    Join,
+    /// Like `join`, also counting the lemma of every part on its own
+    JoinAndParts,
+}
+
+impl From<CompoundsArg> for Compounds {
+    fn from(arg: CompoundsArg) -> Self {
+        match arg {
+            CompoundsArg::Parts => Compounds::Parts,
+            CompoundsArg::Whole => Compounds::Whole,
+            CompoundsArg::Join => Compounds::Join,
+            CompoundsArg::JoinAndParts => Compounds::JoinAndParts,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SplitArg {
+    /// Web addresses, tokenized like any other text
+    Url,
+    /// Email addresses, tokenized like any other text
+    Email,
+    /// Words with digits, dots or underscores, as in `es6` or `Node.js`
+    Identifier,
+    /// Hyphenated words, as in `e-mail`
+    Compound,
+}
+
+/// Options shared by every subcommand that analyzes posts.
+#[derive(Args)]
+pub struct DocumentArgs {
+    /// Id of a post without a `permalink` front matter field
+    #[arg(long, value_enum, default_value_t = FallbackIdArg::FileSlug)]
+    pub fallback_id: FallbackIdArg,
+
+    /// Don't count words of image alt texts
+    #[arg(long)]
+    pub skip_alt_text: bool,
+
+    /// Times a word counts depending on where it occurs, e.g. `title=3,headings=2`;
+    /// fields are title, description, tags, headings, body and link-text, and 0
+    /// leaves a field out [default: headings, body and link-text count once]
+    #[arg(long = "weight", value_delimiter = ',', value_parser = parse_weight)]
+    pub weights: Vec<(Field, u32)>,
+}
+
+fn parse_weight(arg: &str) -> Result<(Field, u32), String> {
+    let (field, weight) = arg.split_once('=').ok_or("expected `field=weight`")?;
+    let weight = weight.parse().map_err(|e| format!("{}: {}", weight, e))?;
+    Ok((field.parse()?, weight))
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum FallbackIdArg {
+    /// File name without extension, or the directory name of an `index` file
+    FileSlug,
+    /// The `slug` front matter field, or the file name when there's none
+    Slug,
+    /// Path of the file
+    Path,
+}
+
+impl DocumentArgs {
+    pub fn options(&self) -> document::Options {
+        let fallback_id = match self.fallback_id {
+            FallbackIdArg::FileSlug => FallbackId::FileSlug,
+            FallbackIdArg::Slug => FallbackId::Slug,
+            FallbackIdArg::Path => FallbackId::Path,
+        };
+        let mut weights = FieldWeights::default();
+        for (field, weight) in &self.weights {
+            weights.set(*field, *weight);
+        }
+        document::Options {
+            fallback_id,
+            alt_text: !self.skip_alt_text,
+            weights,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum DisambiguationArg {
+    /// Function words first, imperatives and abbreviations last, then the lemma
+    /// with the most forms in the dictionary
+    ReadingPriority,
+    /// Lemma with the most forms in the dictionary
+    MostForms,
+    /// Like `reading-priority`, preferring the lemma most often seen unambiguously
+    /// in the analyzed files
+    Corpus,
+}
+
+impl From<DisambiguationArg> for Disambiguation {
+    fn from(arg: DisambiguationArg) -> Self {
+        match arg {
+            DisambiguationArg::ReadingPriority => Disambiguation::ReadingPriority,
+            DisambiguationArg::MostForms => Disambiguation::MostForms,
+            DisambiguationArg::Corpus => Disambiguation::CorpusVotes,
+        }
+    }
+}
+
+impl LemmatizerArgs {
+    /// Loads the main dictionary with the user dictionaries applied over it.
+    pub fn load_dictionary(&self) -> Result<Dictionary, Error> {
+        let mut dictionary = Dictionary::load(&self.dictionary)?;
+        for path in &self.user_dictionaries {
+            for conflict in dictionary.apply_user_dictionary(path)? {
+                eprintln!(
+                    "Warning: {} overrides `{}`: {} instead of {}",
+                    path.display(),
+                    conflict.form,
+                    conflict.lemmas.join(", "),
+                    conflict.previous.join(", ")
+                );
+            }
+        }
+        Ok(dictionary)
+    }
+
+    pub fn load(&self) -> Result<Lemmatizer, Error> {
+        let (dictionary, stopwords) = rayon::join(
+            || self.load_dictionary(),
+            || build_stopwords(&self.stopwords),
+        );
+        let mut lemmatizer = Lemmatizer::new(dictionary?, stopwords?)
+            .with_disambiguation(self.disambiguation.into())
+            .with_tokenizer(Tokenizer::new(self.rules()))
+            .with_compounds(self.compounds.into())
+            .with_casing(self.casing.into());
+        if let Some(path) = &self.guesser {
+            lemmatizer = lemmatizer.with_guesser(Guesser::load(path)?, self.min_confidence);
+        }
+        if self.fold_diacritics {
+            lemmatizer = lemmatizer.with_diacritics_folding();
+        }
+        if !self.parts_of_speech.is_empty() {
+            lemmatizer =
+                lemmatizer.with_parts_of_speech(self.parts_of_speech.iter().copied().collect());
+        }
+        Ok(lemmatizer)
+    }
+
+    fn rules(&self) -> Rules {
+        let keep = |kind| !self.split.contains(&kind);
+        Rules {
+            urls: keep(SplitArg::Url),
+            emails: keep(SplitArg::Email),
+            identifiers: keep(SplitArg::Identifier),
+            compounds: keep(SplitArg::Compound),
+        }
+    }
+
+    /// Loads the lemmatizer and, if the policy needs it, collects votes from `files`.
+    pub fn load_for(
+        &self,
+        files: &[PathBuf],
+        options: &document::Options,
+    ) -> Result<Lemmatizer, Error> {
+        let mut lemmatizer = self.load()?;
+        if let DisambiguationArg::Corpus = self.disambiguation {
+            lemmatizer.set_votes(collect_votes(files, &lemmatizer, options));
+        }
+        Ok(lemmatizer)
+    }
+}
+
+/// Expands glob patterns into a list of paths.
+pub fn expand_globs(patterns: &[String]) -> Result<Vec<PathBuf>, Error> {
+    let mut files = Vec::new();
+    for pattern in patterns {
+        for path in glob::glob(pattern)? {
+            files.push(path?);
+        }
+    }
+    Ok(files)
+}
+
+/// Analyzes every file, leaving out with a warning those that can't be read or
+/// whose front matter is invalid, so one broken post doesn't stop the run.
+pub fn analyze_files(
+    files: &[PathBuf],
+    lemmatizer: &Lemmatizer,
+    options: &document::Options,
+) -> Vec<Document> {
+    files
+        .par_iter()
+        .filter_map(|path| match analyze_path(path, lemmatizer, options) {
+            Ok(document) => Some(document),
+            Err(e) => {
+                eprintln!("Skipping {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn collect_votes(
+    files: &[PathBuf],
+    lemmatizer: &Lemmatizer,
+    options: &document::Options,
+) -> HashMap<String, u32> {
+    files
+        .par_iter()
+        // Files that can't be analyzed are reported when they are skipped later.
+        .filter_map(|path| {
+            let article = String::from_utf8(std::fs::read(path).ok()?).ok()?;
+            let body = document::clean_body(&article, options).ok()?;
+            Some(lemmatizer.count_votes(&body))
+        })
+        .reduce(HashMap::new, |mut left, right| {
+            for (lemma, votes) in right {
+                *left.entry(lemma).or_insert(0) += votes;
+            }
+            left
+        })
+}
+
+/// Missing words shown when no report file is requested.
+const OOV_SUMMARY_LEN: usize = 10;
+
+/// Writes the report of words missing from the dictionary to `path`, or prints
+/// the most frequent of them when no path is given.
+pub fn report_missing(
+    documents: &[Document],
+    lemmatizer: &Lemmatizer,
+    path: Option<&PathBuf>,
+) -> Result<(), Error> {
+    let report = oov::report(documents, lemmatizer);
+    if let Some(path) = path {
+        oov::write_report(&report, path)
+            .map_err(|e| format!("Couldn't write {}: {}", path.display(), e))?;
+        eprintln!("Wrote {} missing words to {}", report.len(), path.display());
+        return Ok(());
+    }
+
+    if report.is_empty() {
+        return Ok(());
+    }
+    let occurrences: u32 = report.iter().map(|missing| missing.frequency).sum();
+    eprintln!(
+        "{} words missing from the dictionary ({} occurrences), most frequent:",
+        report.len(),
+        occurrences
+    );
+    for missing in report.iter().take(OOV_SUMMARY_LEN) {
+        eprintln!(
+            "  {}\t{}\t{} documents",
+            missing.word,
+            missing.frequency,
+            missing.documents.len()
+        );
+    }
+    Ok(())
+}
+
+/// Writes `contents` to `output`, or to stdout when no output is given.
+pub fn write_output(output: Option<&PathBuf>, contents: &str) -> Result<(), Error> {
+    match output {
+        Some(path) => std::fs::write(path, contents)
+            .map_err(|e| format!("Couldn't write {}: {}", path.display(), e).into()),
+        None => {
+            println!("{}", contents);
+            Ok(())
+        }
+    }
+}
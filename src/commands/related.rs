@@ -0,0 +1,146 @@
+use clap::ValueEnum;
+use lemmatizer::similarity::{self, Scoring, Threshold, Weighting};
+use lemmatizer::{results, Error, Results};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::{
+    analyze_files, expand_globs, report_missing, write_output, DocumentArgs, LemmatizerArgs,
+};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Glob patterns of the Markdown files to analyze
+    #[arg(default_value = "./data/**/*.md*")]
+    inputs: Vec<String>,
+
+    #[command(flatten)]
+    lemmatizer: LemmatizerArgs,
+
+    #[command(flatten)]
+    document: DocumentArgs,
+
+    /// Write words missing from the dictionary to this TSV file instead of
+    /// printing the most frequent ones
+    #[arg(long)]
+    oov_report: Option<PathBuf>,
+
+    /// Where to write the JSON with related posts
+    #[arg(short, long, default_value = "./results.json")]
+    output: PathBuf,
+
+    /// Maximum number of related posts per document
+    #[arg(short = 'n', long, default_value_t = 3)]
+    top: usize,
+
+    /// Leave out related posts scoring less than this
+    #[arg(long, default_value_t = 0.0)]
+    min_score: f32,
+
+    /// Leave out related posts scoring less than this fraction of the best match
+    /// of the same document, between 0 and 1
+    #[arg(long, default_value_t = 0.0)]
+    min_relative_score: f32,
+
+    /// How relatedness of two documents is measured
+    #[arg(long, value_enum, default_value_t = ScoringArg::Cosine)]
+    scoring: ScoringArg,
+
+    /// How lemma counts are weighted for cosine similarity
+    #[arg(long, value_enum, default_value_t = WeightingArg::Counts)]
+    weighting: WeightingArg,
+
+    /// Use `1 + ln(tf)` as the term frequency in TF-IDF
+    #[arg(long)]
+    sublinear_tf: bool,
+
+    /// Use smoothed `ln((1 + n) / (1 + df)) + 1` as the IDF in TF-IDF
+    #[arg(long)]
+    smooth_idf: bool,
+
+    /// BM25 term frequency saturation
+    #[arg(long, default_value_t = 1.2)]
+    k1: f32,
+
+    /// BM25 document length normalisation, between 0 and 1
+    #[arg(long, default_value_t = 0.75)]
+    b: f32,
+
+    /// Version of the output format: 2 includes scores and front matter of related
+    /// posts, 1 only maps permalinks to related permalinks
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..=2), default_value_t = results::SCHEMA_VERSION)]
+    format: u32,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ScoringArg {
+    /// Cosine similarity of weighted lemma counts
+    Cosine,
+    /// Okapi BM25 with each document as a query against the others
+    Bm25,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum WeightingArg {
+    /// Raw lemma counts
+    Counts,
+    /// Term frequency times inverse document frequency
+    TfIdf,
+}
+
+impl Args {
+    fn scoring(&self) -> Scoring {
+        let weighting = match self.weighting {
+            WeightingArg::Counts => Weighting::Counts,
+            WeightingArg::TfIdf => Weighting::TfIdf {
+                sublinear_tf: self.sublinear_tf,
+                smooth_idf: self.smooth_idf,
+            },
+        };
+        match self.scoring {
+            ScoringArg::Cosine => Scoring::Cosine(weighting),
+            ScoringArg::Bm25 => Scoring::Bm25 {
+                k1: self.k1,
+                b: self.b,
+            },
+        }
+    }
+
+    fn threshold(&self) -> Threshold {
+        Threshold {
+            absolute: self.min_score,
+            relative: self.min_relative_score,
+        }
+    }
+}
+
+pub fn run(args: Args) -> Result<(), Error> {
+    let files = expand_globs(&args.inputs)?;
+    let options = args.document.options();
+    let lemmatizer = args.lemmatizer.load_for(&files, &options)?;
+    let analyzed_files = analyze_files(&files, &lemmatizer, &options);
+    report_missing(&analyzed_files, &lemmatizer, args.oov_report.as_ref())?;
+
+    println!("{}", analyzed_files.len());
+
+    let top_matches =
+        similarity::top_matches(&analyzed_files, args.scoring(), args.top, args.threshold());
+
+    let json = if args.format == 1 {
+        let top_similarities_per_files = analyzed_files
+            .iter()
+            .zip(&top_matches)
+            .map(|(document, matches)| {
+                let permalinks = matches
+                    .iter()
+                    .map(|m| analyzed_files[m.document].permalink.as_str())
+                    .collect::<Vec<&str>>();
+                (document.permalink.as_str(), permalinks)
+            })
+            .collect::<HashMap<&str, Vec<&str>>>();
+        serde_json::to_string(&top_similarities_per_files)?
+    } else {
+        serde_json::to_string(&Results::new(&analyzed_files, &top_matches))?
+    };
+    write_output(Some(&args.output), &json)
+}
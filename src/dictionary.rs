@@ -0,0 +1,338 @@
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, Read};
+use std::path::Path;
+
+use crate::tags::{self, PartOfSpeech, Tag};
+use crate::Error;
+
+mod compiled;
+mod folded;
+mod user;
+
+pub use compiled::CompiledDictionary;
+pub use folded::{fold, FoldedIndex};
+pub use user::Conflict;
+
+/// One reading of a word form: its lemma and the raw morphosyntactic tags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Entry<'a> {
+    pub lemma: &'a str,
+    /// Tag column as found in the dictionary, empty when the line has none.
+    pub raw_tags: &'a str,
+}
+
+impl<'a> Entry<'a> {
+    pub fn tags(self) -> Vec<Tag> {
+        tags::parse_tags(self.raw_tags)
+    }
+
+    pub fn parts_of_speech(self) -> impl Iterator<Item = PartOfSpeech> + 'a {
+        tags::parts_of_speech(self.raw_tags)
+    }
+}
+
+/// Reading stored in memory, as ids into the lemma and tag interners.
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    lemma: u32,
+    tags: u32,
+}
+
+/// Stores every distinct string once and hands out dense ids in insertion order.
+#[derive(Debug, Default)]
+struct Interner {
+    ids: HashMap<Box<str>, u32>,
+    strings: Vec<Box<str>>,
+}
+
+impl Interner {
+    fn intern(&mut self, string: &str) -> u32 {
+        if let Some(&id) = self.ids.get(string) {
+            return id;
+        }
+        let id = self.strings.len() as u32;
+        self.strings.push(string.into());
+        self.ids.insert(string.into(), id);
+        id
+    }
+
+    fn id(&self, string: &str) -> Option<u32> {
+        self.ids.get(string).copied()
+    }
+
+    fn get(&self, id: u32) -> &str {
+        &self.strings[id as usize]
+    }
+}
+
+/// Size of the text chunks handed from the decompressing thread to the parser.
+const CHUNK_SIZE: usize = 4 << 20;
+
+/// The PoliMorf dump holds roughly one word form per 5 bytes of brotli output,
+/// used to pre-size the tables before loading.
+const COMPRESSED_BYTES_PER_FORM: u64 = 5;
+
+/// Maps every word form to all of its candidate lemmas.
+///
+/// A dictionary is either built in memory from the brotli-compressed text source,
+/// or opened from a file written by [`Dictionary::compile`]. Entries inserted into
+/// an opened compiled dictionary are kept in memory on top of it.
+///
+/// Candidates of a form are kept sorted, so lookups don't depend on the order in
+/// which the dictionary lines were read.
+#[derive(Debug, Default)]
+pub struct Dictionary {
+    compiled: Option<CompiledDictionary>,
+    forms: HashMap<Box<str>, Vec<Candidate>>,
+    lemmas: Interner,
+    tags: Interner,
+    /// Number of entries per lemma id.
+    paradigm_sizes: Vec<u32>,
+    /// Forms whose compiled readings were replaced by [`Dictionary::replace`].
+    hidden: HashSet<Box<str>>,
+}
+
+impl Dictionary {
+    /// Opens a compiled dictionary or reads a brotli-compressed text one,
+    /// depending on the contents of the file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let mut magic = [0; compiled::MAGIC.len()];
+        let is_compiled =
+            std::fs::File::open(path)?.read_exact(&mut magic).is_ok() && magic == compiled::MAGIC;
+
+        if is_compiled {
+            Dictionary::open_compiled(path)
+        } else {
+            Dictionary::load_source(path)
+        }
+    }
+
+    /// Memory-maps a dictionary written by [`Dictionary::compile`].
+    pub fn open_compiled(path: impl AsRef<Path>) -> Result<Self, Error> {
+        eprintln!("Opening compiled dictionary…");
+        Ok(Dictionary {
+            compiled: Some(CompiledDictionary::open(path)?),
+            ..Dictionary::default()
+        })
+    }
+
+    /// Reads a brotli-compressed `lemma;form[;tags]` file.
+    ///
+    /// Decompression runs on its own thread and hands over chunks of whole lines,
+    /// which are split in parallel and inserted in file order. The result, including
+    /// interned ids, is the same on every run.
+    pub fn load_source(path: impl AsRef<Path>) -> Result<Self, Error> {
+        eprintln!("Reading dictionary file…");
+        let file = std::fs::File::open(path)?;
+        let estimated_forms = file.metadata()?.len() / COMPRESSED_BYTES_PER_FORM;
+
+        let (sender, receiver) = std::sync::mpsc::sync_channel(2);
+        let reader = std::thread::spawn(move || read_chunks(file, sender));
+
+        eprintln!("Building dictionary HashMap…");
+        let mut dictionary = Dictionary::with_capacity(estimated_forms as usize);
+        for chunk in receiver {
+            let chunk: String = chunk?;
+            let lines = chunk
+                .par_lines()
+                .filter_map(parse_line)
+                .collect::<Vec<(&str, Entry)>>();
+            for (form, entry) in lines {
+                dictionary.insert(form, entry.lemma, entry.raw_tags);
+            }
+        }
+        reader
+            .join()
+            .map_err(|_| "Dictionary reader thread panicked")?;
+
+        dictionary.forms.shrink_to_fit();
+        Ok(dictionary)
+    }
+
+    fn with_capacity(forms: usize) -> Self {
+        Dictionary {
+            forms: HashMap::with_capacity(forms),
+            ..Dictionary::default()
+        }
+    }
+
+    /// Writes the dictionary in the compact format read by [`Dictionary::open_compiled`].
+    pub fn compile(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let mut forms = Vec::with_capacity(self.len());
+        self.for_each_form(|form, entries| forms.push((form.to_string(), entries.to_vec())));
+        compiled::write(path.as_ref(), &mut forms)
+    }
+
+    pub fn insert(&mut self, form: &str, lemma: &str, raw_tags: &str) {
+        let candidate = Candidate {
+            lemma: self.lemmas.intern(lemma),
+            tags: self.tags.intern(raw_tags),
+        };
+        if self.paradigm_sizes.len() < self.lemmas.strings.len() {
+            self.paradigm_sizes.resize(self.lemmas.strings.len(), 0);
+        }
+
+        let (lemmas, tags) = (&self.lemmas, &self.tags);
+        let key = |c: &Candidate| (lemmas.get(c.lemma), tags.get(c.tags));
+        let candidates = match self.forms.get_mut(form) {
+            Some(candidates) => candidates,
+            None => self.forms.entry(form.into()).or_default(),
+        };
+        if let Err(position) = candidates.binary_search_by(|c| key(c).cmp(&(lemma, raw_tags))) {
+            self.paradigm_sizes[candidate.lemma as usize] += 1;
+            candidates.insert(position, candidate);
+        }
+    }
+
+    /// Replaces all readings of a form with the given `(lemma, tags)` pairs.
+    pub fn replace(&mut self, form: &str, readings: &[(&str, &str)]) {
+        if let Some(candidates) = self.forms.remove(form) {
+            for candidate in candidates {
+                self.paradigm_sizes[candidate.lemma as usize] -= 1;
+            }
+        }
+        if self.is_compiled_form(form) {
+            self.hidden.insert(form.into());
+        }
+        for (lemma, raw_tags) in readings {
+            self.insert(form, lemma, raw_tags);
+        }
+    }
+
+    fn entry(&self, candidate: &Candidate) -> Entry<'_> {
+        Entry {
+            lemma: self.lemmas.get(candidate.lemma),
+            raw_tags: self.tags.get(candidate.tags),
+        }
+    }
+
+    /// All readings of a word form, sorted by lemma and tags.
+    pub fn candidates(&self, form: &str) -> Vec<Entry<'_>> {
+        let mut candidates = match &self.compiled {
+            Some(compiled) if !self.hidden.contains(form) => compiled.candidates(form),
+            _ => Vec::new(),
+        };
+        if let Some(entries) = self.forms.get(form) {
+            candidates.extend(entries.iter().map(|candidate| self.entry(candidate)));
+            candidates.sort_unstable();
+            candidates.dedup();
+        }
+        candidates
+    }
+
+    /// Number of dictionary entries of a lemma, used to prefer common paradigms.
+    pub fn paradigm_size(&self, lemma: &str) -> u32 {
+        let compiled = self
+            .compiled
+            .as_ref()
+            .map_or(0, |compiled| compiled.paradigm_size(lemma));
+        let in_memory = self
+            .lemmas
+            .id(lemma)
+            .map_or(0, |id| self.paradigm_sizes[id as usize]);
+        compiled + in_memory
+    }
+
+    /// Calls `f` with every word form and its candidates, compiled forms in
+    /// lexicographic order first.
+    pub fn for_each_form<'a>(&'a self, mut f: impl FnMut(&str, &[Entry<'a>])) {
+        if let Some(compiled) = &self.compiled {
+            compiled.for_each_form(|form| f(form, &self.candidates(form)));
+        }
+        for form in self.forms.keys() {
+            if !self.is_compiled_form(form) {
+                f(form, &self.candidates(form));
+            }
+        }
+    }
+
+    /// Calls `f` with every word form, like [`Dictionary::for_each_form`] without
+    /// looking up the candidates.
+    pub fn for_each_form_name(&self, mut f: impl FnMut(&str)) {
+        if let Some(compiled) = &self.compiled {
+            compiled.for_each_form(&mut f);
+        }
+        for form in self.forms.keys() {
+            if !self.is_compiled_form(form) {
+                f(form);
+            }
+        }
+    }
+
+    fn is_compiled_form(&self, form: &str) -> bool {
+        self.compiled
+            .as_ref()
+            .is_some_and(|compiled| compiled.contains(form))
+    }
+
+    /// Number of distinct word forms.
+    pub fn len(&self) -> usize {
+        let compiled = self.compiled.as_ref().map_or(0, CompiledDictionary::len);
+        let in_memory = self
+            .forms
+            .keys()
+            .filter(|form| !self.is_compiled_form(form))
+            .count();
+        compiled + in_memory
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of distinct lemmas.
+    pub fn lemma_count(&self) -> usize {
+        let compiled = self
+            .compiled
+            .as_ref()
+            .map_or(0, CompiledDictionary::lemma_count);
+        // Lemmas whose forms were all replaced by a user dictionary are gone.
+        let in_memory = self
+            .lemmas
+            .strings
+            .iter()
+            .zip(&self.paradigm_sizes)
+            .filter(|(lemma, size)| {
+                **size > 0
+                    && self
+                        .compiled
+                        .as_ref()
+                        .is_none_or(|compiled| compiled.paradigm_size(lemma) == 0)
+            })
+            .count();
+        compiled + in_memory
+    }
+}
+
+/// Sends the decompressed dictionary in chunks that end at a line boundary.
+fn read_chunks(file: std::fs::File, sender: std::sync::mpsc::SyncSender<Result<String, Error>>) {
+    let file = brotli::Decompressor::new(file, 4096 /* buffer size */);
+    let mut reader = std::io::BufReader::new(file);
+    loop {
+        let mut chunk = String::with_capacity(CHUNK_SIZE + 256);
+        while chunk.len() < CHUNK_SIZE {
+            match reader.read_line(&mut chunk) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    // The receiver reports the error, so a failed send can be ignored.
+                    let _ = sender.send(Err(e.into()));
+                    return;
+                }
+            }
+        }
+        if chunk.is_empty() || sender.send(Ok(chunk)).is_err() {
+            return;
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Option<(&str, Entry<'_>)> {
+    let mut columns = line.split(';');
+    let lemma = columns.next()?;
+    let form = columns.next()?;
+    let raw_tags = columns.next().unwrap_or("");
+    Some((form, Entry { lemma, raw_tags }))
+}
@@ -0,0 +1,366 @@
+//! Compact on-disk dictionary that is memory-mapped instead of being loaded.
+//!
+//! The file starts with [`MAGIC`] and the byte lengths of the sections that follow,
+//! all integers being little-endian:
+//!
+//! * an FST mapping every form to the offset of its candidate list,
+//! * candidate lists: `u32` count followed by `(u32 lemma id, u32 tags id)` pairs,
+//! * lemma table: `u32` offsets into the lemma bytes, one more than there are lemmas,
+//! * lemma bytes, lemmas sorted so that ids can be found by binary search,
+//! * paradigm sizes: one `u32` per lemma id,
+//! * tags table and tags bytes, laid out like the lemmas.
+
+use fst::Streamer;
+use memmap2::Mmap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::Write;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::Entry;
+use crate::Error;
+
+pub(super) const MAGIC: [u8; 8] = *b"LEMDICT\x01";
+const SECTIONS: usize = 7;
+const HEADER_LEN: usize = MAGIC.len() + SECTIONS * 8;
+
+/// Part of the mapped file, so that the FST can own its bytes.
+struct Section {
+    data: Arc<Mmap>,
+    range: Range<usize>,
+}
+
+impl AsRef<[u8]> for Section {
+    fn as_ref(&self) -> &[u8] {
+        &self.data[self.range.clone()]
+    }
+}
+
+/// Offsets table followed by the concatenated strings.
+struct StringTable {
+    offsets: Range<usize>,
+    bytes: Range<usize>,
+}
+
+impl StringTable {
+    fn len(&self) -> usize {
+        (self.offsets.len() / 4).saturating_sub(1)
+    }
+
+    fn get<'a>(&self, data: &'a [u8], id: usize) -> &'a str {
+        let start = read_u32(data, self.offsets.start + id * 4) as usize;
+        let end = read_u32(data, self.offsets.start + (id + 1) * 4) as usize;
+        let bytes = &data[self.bytes.clone()];
+        std::str::from_utf8(&bytes[start..end]).expect("string tables are validated when opened")
+    }
+
+    /// Checks that the offsets start at 0, never decrease and end within the
+    /// bytes, and that they delimit valid UTF-8 strings in strictly increasing order.
+    fn validate(&self, data: &[u8], name: &str) -> Result<(), Error> {
+        let invalid = |reason: &str| format!("Invalid {} table: {}", name, reason);
+        if self.offsets.len() < 4 || !self.offsets.len().is_multiple_of(4) {
+            return Err(invalid("bad offsets length").into());
+        }
+        if read_u32(data, self.offsets.start) != 0 {
+            return Err(invalid("first offset isn't 0").into());
+        }
+        let bytes = &data[self.bytes.clone()];
+        let mut previous: Option<&str> = None;
+        let mut start = 0;
+        for id in 0..self.len() {
+            let end = read_u32(data, self.offsets.start + (id + 1) * 4) as usize;
+            if end < start || end > bytes.len() {
+                return Err(invalid("offset out of bounds").into());
+            }
+            let string = std::str::from_utf8(&bytes[start..end])
+                .map_err(|_| invalid("string isn't valid UTF-8"))?;
+            if previous.is_some_and(|previous| previous >= string) {
+                return Err(invalid("strings aren't sorted").into());
+            }
+            previous = Some(string);
+            start = end;
+        }
+        Ok(())
+    }
+}
+
+pub struct CompiledDictionary {
+    data: Arc<Mmap>,
+    forms: fst::Map<Section>,
+    candidates: Range<usize>,
+    lemmas: StringTable,
+    paradigm_sizes: Range<usize>,
+    tags: StringTable,
+}
+
+impl fmt::Debug for CompiledDictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompiledDictionary")
+            .field("forms", &self.forms.len())
+            .field("lemmas", &self.lemmas.len())
+            .field("tags", &self.tags.len())
+            .finish()
+    }
+}
+
+impl CompiledDictionary {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: the file is only read, and compiled dictionaries are not expected
+        // to be modified while in use.
+        let data = Arc::new(unsafe { Mmap::map(&file)? });
+
+        if data.len() < HEADER_LEN || data[..MAGIC.len()] != MAGIC {
+            return Err("Not a compiled dictionary".into());
+        }
+
+        let mut sections = Vec::with_capacity(SECTIONS);
+        let mut start = HEADER_LEN;
+        for i in 0..SECTIONS {
+            let end = usize::try_from(read_u64(&data, MAGIC.len() + i * 8))
+                .ok()
+                .and_then(|len| start.checked_add(len))
+                .filter(|end| *end <= data.len())
+                .ok_or("Truncated compiled dictionary")?;
+            sections.push(start..end);
+            start = end;
+        }
+        if start != data.len() {
+            return Err("Compiled dictionary has trailing bytes".into());
+        }
+
+        let forms = fst::Map::new(Section {
+            data: Arc::clone(&data),
+            range: sections[0].clone(),
+        })?;
+
+        let dictionary = CompiledDictionary {
+            forms,
+            candidates: sections[1].clone(),
+            lemmas: StringTable {
+                offsets: sections[2].clone(),
+                bytes: sections[3].clone(),
+            },
+            paradigm_sizes: sections[4].clone(),
+            tags: StringTable {
+                offsets: sections[5].clone(),
+                bytes: sections[6].clone(),
+            },
+            data,
+        };
+        dictionary.validate()?;
+        Ok(dictionary)
+    }
+
+    /// Checks the string tables, the paradigm sizes, every candidate list and that
+    /// every form points at the start of one, so lookups can't read past a section
+    /// or hit an unknown lemma or tags id.
+    fn validate(&self) -> Result<(), Error> {
+        self.lemmas.validate(&self.data, "lemma")?;
+        self.tags.validate(&self.data, "tags")?;
+        if self.paradigm_sizes.len() != self.lemmas.len() * 4 {
+            return Err("Paradigm sizes don't match the lemmas".into());
+        }
+
+        let (lemmas, tags) = (self.lemmas.len(), self.tags.len());
+        // Lists are made of u32s, so they start at multiples of 4, marked at a quarter
+        // of their offset.
+        let mut starts = vec![false; self.candidates.len() / 4];
+        let mut position = self.candidates.start;
+        while position < self.candidates.end {
+            let end = self
+                .candidate_list(position - self.candidates.start)
+                .ok_or("Candidate list out of bounds")?;
+            starts[(position - self.candidates.start) / 4] = true;
+            for pair in (position + 4..end).step_by(8) {
+                if read_u32(&self.data, pair) as usize >= lemmas
+                    || read_u32(&self.data, pair + 4) as usize >= tags
+                {
+                    return Err("Candidate with an unknown lemma or tags id".into());
+                }
+            }
+            position = end;
+        }
+
+        let mut forms = self.forms.stream();
+        while let Some((_, offset)) = forms.next() {
+            let starts_list = offset % 4 == 0
+                && usize::try_from(offset / 4).is_ok_and(|index| starts.get(index) == Some(&true));
+            if !starts_list {
+                return Err("Form pointing inside a candidate list".into());
+            }
+        }
+        Ok(())
+    }
+
+    /// End of the candidate list at `offset` in the candidates section, if it
+    /// fits in the section.
+    fn candidate_list(&self, offset: usize) -> Option<usize> {
+        let start = self.candidates.start.checked_add(offset)?;
+        if start.checked_add(4)? > self.candidates.end {
+            return None;
+        }
+        let count = read_u32(&self.data, start) as usize;
+        let end = start.checked_add(4)?.checked_add(count.checked_mul(8)?)?;
+        (end <= self.candidates.end).then_some(end)
+    }
+
+    /// Readings of a form, none when the FST points outside of the candidate lists.
+    pub fn candidates(&self, form: &str) -> Vec<Entry<'_>> {
+        let offset = match self.forms.get(form) {
+            Some(offset) => offset as usize,
+            None => return Vec::new(),
+        };
+        let (start, end) = match self.candidate_list(offset) {
+            Some(end) => (self.candidates.start + offset, end),
+            None => return Vec::new(),
+        };
+        (start + 4..end)
+            .step_by(8)
+            .map(|pair| {
+                let lemma = read_u32(&self.data, pair) as usize;
+                let tags = read_u32(&self.data, pair + 4) as usize;
+                Entry {
+                    lemma: self.lemmas.get(&self.data, lemma),
+                    raw_tags: self.tags.get(&self.data, tags),
+                }
+            })
+            .collect()
+    }
+
+    pub fn contains(&self, form: &str) -> bool {
+        self.forms.contains_key(form)
+    }
+
+    pub fn paradigm_size(&self, lemma: &str) -> u32 {
+        let (mut low, mut high) = (0, self.lemmas.len());
+        while low < high {
+            let middle = (low + high) / 2;
+            match self.lemmas.get(&self.data, middle).cmp(lemma) {
+                std::cmp::Ordering::Less => low = middle + 1,
+                std::cmp::Ordering::Greater => high = middle,
+                std::cmp::Ordering::Equal => {
+                    return read_u32(&self.data, self.paradigm_sizes.start + middle * 4);
+                }
+            }
+        }
+        0
+    }
+
+    /// Calls `f` with every form in lexicographic order.
+    pub fn for_each_form(&self, mut f: impl FnMut(&str)) {
+        let mut stream = self.forms.stream();
+        while let Some((form, _)) = stream.next() {
+            if let Ok(form) = std::str::from_utf8(form) {
+                f(form);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.forms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.forms.is_empty()
+    }
+
+    pub fn lemma_count(&self) -> usize {
+        self.lemmas.len()
+    }
+}
+
+/// Writes `forms` in the compiled format.
+pub(super) fn write(path: &Path, forms: &mut [(String, Vec<Entry<'_>>)]) -> Result<(), Error> {
+    forms.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut paradigm_sizes: BTreeMap<&str, u32> = BTreeMap::new();
+    let mut tags: BTreeSet<&str> = BTreeSet::new();
+    for (_, entries) in forms.iter() {
+        for entry in entries {
+            *paradigm_sizes.entry(entry.lemma).or_insert(0) += 1;
+            tags.insert(entry.raw_tags);
+        }
+    }
+    let lemma_ids: BTreeMap<&str, u32> = paradigm_sizes
+        .keys()
+        .enumerate()
+        .map(|(id, lemma)| (*lemma, id as u32))
+        .collect();
+    let tag_ids: BTreeMap<&str, u32> = tags
+        .iter()
+        .enumerate()
+        .map(|(id, tags)| (*tags, id as u32))
+        .collect();
+
+    let mut fst = fst::MapBuilder::memory();
+    let mut candidates = Vec::new();
+    for (form, entries) in forms.iter() {
+        if form.is_empty() {
+            continue;
+        }
+        if fst.insert(form, candidates.len() as u64).is_err() {
+            // Identical forms are already merged, the FST only rejects empty keys.
+            continue;
+        }
+        candidates.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        for entry in entries {
+            candidates.extend_from_slice(&lemma_ids[entry.lemma].to_le_bytes());
+            candidates.extend_from_slice(&tag_ids[entry.raw_tags].to_le_bytes());
+        }
+    }
+    let fst = fst.into_inner()?;
+
+    let (lemma_offsets, lemma_bytes) = string_table(paradigm_sizes.keys().copied());
+    let sizes = paradigm_sizes
+        .values()
+        .flat_map(|size| size.to_le_bytes())
+        .collect::<Vec<u8>>();
+    let (tag_offsets, tag_bytes) = string_table(tags.iter().copied());
+
+    let sections: [&[u8]; SECTIONS] = [
+        &fst,
+        &candidates,
+        &lemma_offsets,
+        &lemma_bytes,
+        &sizes,
+        &tag_offsets,
+        &tag_bytes,
+    ];
+
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    file.write_all(&MAGIC)?;
+    for section in &sections {
+        file.write_all(&(section.len() as u64).to_le_bytes())?;
+    }
+    for section in &sections {
+        file.write_all(section)?;
+    }
+    file.flush()?;
+    Ok(())
+}
+
+fn string_table<'a>(strings: impl Iterator<Item = &'a str>) -> (Vec<u8>, Vec<u8>) {
+    let mut offsets = 0u32.to_le_bytes().to_vec();
+    let mut bytes = Vec::new();
+    for string in strings {
+        bytes.extend_from_slice(string.as_bytes());
+        offsets.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    }
+    (offsets, bytes)
+}
+
+fn read_u32(data: &[u8], position: usize) -> u32 {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(&data[position..position + 4]);
+    u32::from_le_bytes(bytes)
+}
+
+fn read_u64(data: &[u8], position: usize) -> u64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&data[position..position + 8]);
+    u64::from_le_bytes(bytes)
+}
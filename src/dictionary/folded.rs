@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+use unicode_normalization::char::decompose_canonical;
+
+use super::Dictionary;
+
+/// Strips diacritics, so `żółw` and `zolw` fold to the same string.
+///
+/// `ł` has no canonical decomposition and is folded to `l` explicitly.
+pub fn fold(word: &str) -> String {
+    word.chars().map(fold_char).collect()
+}
+
+fn fold_char(c: char) -> char {
+    match c {
+        'ł' => 'l',
+        'Ł' => 'L',
+        c if c.is_ascii() => c,
+        c => {
+            let mut base = c;
+            let mut first = true;
+            decompose_canonical(c, |part| {
+                if first {
+                    base = part;
+                    first = false;
+                }
+            });
+            base
+        }
+    }
+}
+
+/// Forms of a dictionary that contain diacritics, by their folded spelling.
+#[derive(Debug, Default)]
+pub struct FoldedIndex {
+    forms: HashMap<Box<str>, Vec<Box<str>>>,
+}
+
+impl FoldedIndex {
+    pub fn new(dictionary: &Dictionary) -> Self {
+        eprintln!("Building index of forms without diacritics…");
+        let mut forms: HashMap<Box<str>, Vec<Box<str>>> = HashMap::new();
+        dictionary.for_each_form_name(|form| {
+            let folded = fold(form);
+            if folded != form {
+                forms.entry(folded.into()).or_default().push(form.into());
+            }
+        });
+        for spellings in forms.values_mut() {
+            spellings.sort_unstable();
+            spellings.dedup();
+        }
+        FoldedIndex { forms }
+    }
+
+    /// Forms with diacritics that fold to `folded`, in lexicographic order.
+    pub fn forms(&self, folded: &str) -> &[Box<str>] {
+        self.forms.get(folded).map_or(&[], Vec::as_slice)
+    }
+
+    /// Number of folded spellings.
+    pub fn len(&self) -> usize {
+        self.forms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.forms.is_empty()
+    }
+}
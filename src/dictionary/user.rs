@@ -0,0 +1,186 @@
+//! Project-specific dictionaries layered over the main one.
+//!
+//! A user dictionary is one of:
+//!
+//! * `lemma;form[;tags]` lines like the main dictionary, brotli-compressed when the
+//!   file name ends with `.br`,
+//! * a `.csv` file with `form,lemma[,tags]` lines, optionally after a header line
+//!   naming these columns,
+//! * a `.toml` file with `form = "lemma"` or `form = ["lemma", …]` keys, quoted
+//!   when they have letters outside of ASCII, as in `"żółwia" = "żółw"`.
+//!
+//! Empty lines and lines starting with `#` are skipped in the text formats. Forms
+//! are kept as written, like in the main dictionary, so that capitalised forms of
+//! proper nouns can be added too. They are also added lowercased, since words are
+//! lowercased before lookup by default, unless the dictionary already knows the
+//! lowercased spelling.
+//!
+//! A form listed in a user dictionary replaces all readings the dictionary had for
+//! it, so applying several user dictionaries gives precedence to the later ones.
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, Read};
+use std::path::Path;
+
+use super::Dictionary;
+use crate::Error;
+
+/// A form whose lemmas were changed by a user dictionary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub form: String,
+    pub previous: Vec<String>,
+    pub lemmas: Vec<String>,
+}
+
+/// `form → (lemma, tags)` readings, in file order.
+type Readings = BTreeMap<String, Vec<(String, String)>>;
+
+impl Dictionary {
+    /// Adds the entries of a user dictionary, replacing the readings of every form it
+    /// lists. Returns the forms that had different lemmas before.
+    pub fn apply_user_dictionary(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<Vec<Conflict>, Error> {
+        let path = path.as_ref();
+        let mut readings =
+            read_user_dictionary(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let mut lowercased = Readings::new();
+        for (form, entries) in &readings {
+            let form = form.to_lowercase();
+            if readings.contains_key(&form) || !self.candidates(&form).is_empty() {
+                continue;
+            }
+            let merged = lowercased.entry(form).or_default();
+            for entry in entries {
+                if !merged.contains(entry) {
+                    merged.push(entry.clone());
+                }
+            }
+        }
+        readings.extend(lowercased);
+
+        let mut conflicts = Vec::new();
+        for (form, entries) in &readings {
+            let mut previous = self
+                .candidates(form)
+                .iter()
+                .map(|entry| entry.lemma.to_string())
+                .collect::<Vec<String>>();
+            previous.dedup();
+            let mut lemmas = entries
+                .iter()
+                .map(|(lemma, _)| lemma.clone())
+                .collect::<Vec<String>>();
+            lemmas.sort_unstable();
+            lemmas.dedup();
+            if !previous.is_empty() && previous != lemmas {
+                conflicts.push(Conflict {
+                    form: form.clone(),
+                    previous,
+                    lemmas,
+                });
+            }
+
+            let entries = entries
+                .iter()
+                .map(|(lemma, tags)| (lemma.as_str(), tags.as_str()))
+                .collect::<Vec<_>>();
+            self.replace(form, &entries);
+        }
+        Ok(conflicts)
+    }
+}
+
+fn read_user_dictionary(path: &Path) -> Result<Readings, Error> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("");
+    let file = std::fs::File::open(path)?;
+
+    let mut readings = Readings::new();
+    let mut add = |form: &str, lemma: &str, tags: &str| {
+        let form = form.trim().to_string();
+        let lemma = lemma.trim();
+        if !form.is_empty() && !lemma.is_empty() {
+            let entry = (lemma.to_string(), tags.trim().to_string());
+            let entries = readings.entry(form).or_default();
+            if !entries.contains(&entry) {
+                entries.push(entry);
+            }
+        }
+    };
+
+    match extension {
+        "toml" => {
+            let mut contents = String::new();
+            std::io::BufReader::new(file).read_to_string(&mut contents)?;
+            let table = contents.parse::<toml::Table>()?;
+            for (form, value) in table {
+                match value {
+                    toml::Value::String(lemma) => add(&form, &lemma, ""),
+                    toml::Value::Array(lemmas) => {
+                        for lemma in lemmas {
+                            let lemma = lemma
+                                .as_str()
+                                .ok_or_else(|| format!("Lemmas of `{}` must be strings", form))?;
+                            add(&form, lemma, "");
+                        }
+                    }
+                    _ => return Err(format!("Lemma of `{}` must be a string", form).into()),
+                }
+            }
+        }
+        "csv" => {
+            for (index, line) in text_lines(std::io::BufReader::new(file)).enumerate() {
+                let line = line?;
+                if index == 0 && is_csv_header(&line) {
+                    continue;
+                }
+                let mut columns = line.split(',');
+                match (columns.next(), columns.next()) {
+                    (Some(form), Some(lemma)) => add(form, lemma, columns.next().unwrap_or("")),
+                    _ => return Err(format!("Expected `form,lemma`, got `{}`", line).into()),
+                }
+            }
+        }
+        _ => {
+            let reader: Box<dyn BufRead> = if extension == "br" {
+                Box::new(std::io::BufReader::new(brotli::Decompressor::new(
+                    file, 4096, /* buffer size */
+                )))
+            } else {
+                Box::new(std::io::BufReader::new(file))
+            };
+            for line in text_lines(reader) {
+                let line = line?;
+                let mut columns = line.split(';');
+                match (columns.next(), columns.next()) {
+                    (Some(lemma), Some(form)) => add(form, lemma, columns.next().unwrap_or("")),
+                    _ => return Err(format!("Expected `lemma;form`, got `{}`", line).into()),
+                }
+            }
+        }
+    }
+
+    Ok(readings)
+}
+
+/// Whether `line` names the `form,lemma[,tags]` columns.
+fn is_csv_header(line: &str) -> bool {
+    let columns = line
+        .split(',')
+        .map(|column| column.trim().to_lowercase())
+        .collect::<Vec<String>>();
+    columns == ["form", "lemma"] || columns == ["form", "lemma", "tags"]
+}
+
+/// Lines of a text dictionary without blank lines and `#` comments.
+fn text_lines(reader: impl BufRead) -> impl Iterator<Item = std::io::Result<String>> {
+    reader.lines().filter(|line| match line {
+        Ok(line) => !line.trim().is_empty() && !line.starts_with('#'),
+        Err(_) => true,
+    })
+}
@@ -0,0 +1,165 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::{Error, Lemmatizer, WordCounts};
+
+mod fields;
+mod front_matter;
+mod markdown;
+
+pub use fields::{Field, FieldWeights};
+pub use front_matter::split as split_front_matter;
+
+/// Result of analyzing a single article.
+#[derive(Debug, Clone)]
+pub struct Document {
+    pub permalink: String,
+    pub counter: HashMap<String, u32>,
+    /// Words missing from the dictionary with their number of occurrences.
+    pub missing: HashMap<String, u32>,
+    /// Front matter fields such as `title` or `date`.
+    pub metadata: Map<String, Value>,
+}
+
+/// Where the id of a post without a `permalink` field comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FallbackId {
+    /// File name without extension as a slug, or the directory name of an `index` file.
+    #[default]
+    FileSlug,
+    /// The `slug` front matter field, or the file name slug when there's none.
+    Slug,
+    /// Path of the file as given.
+    Path,
+}
+
+/// How articles are turned into documents.
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub fallback_id: FallbackId,
+    /// Count words of image alt texts.
+    pub alt_text: bool,
+    pub weights: FieldWeights,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            fallback_id: FallbackId::default(),
+            alt_text: true,
+            weights: FieldWeights::default(),
+        }
+    }
+}
+
+pub fn analyze_path(
+    path: impl AsRef<Path>,
+    lemmatizer: &Lemmatizer,
+    options: &Options,
+) -> Result<Document, Error> {
+    let path = path.as_ref();
+    let article = String::from_utf8(std::fs::read(path)?)?;
+    analyze(&article, path, lemmatizer, options)
+}
+
+/// Analyzes the article read from `path`, which is only used for the id of an
+/// article without a permalink.
+///
+/// Front matter values are kept as written, only counted words are lowercased.
+pub fn analyze(
+    article: &str,
+    path: &Path,
+    lemmatizer: &Lemmatizer,
+    options: &Options,
+) -> Result<Document, Error> {
+    let (metadata, body) = front_matter::split(article)?;
+    let permalink = id(&metadata, path, options.fallback_id)?;
+
+    let prose = markdown::prose(body, options.alt_text);
+    let mut counts = WordCounts::default();
+    for field in Field::ALL {
+        let weight = options.weights.get(field);
+        if weight == 0 {
+            continue;
+        }
+        match prose.get(field) {
+            Some(text) => lemmatizer.add_words(text, weight, &mut counts),
+            None => {
+                let text = field.metadata_text(&metadata).unwrap_or_default();
+                lemmatizer.add_words(&text, weight, &mut counts)
+            }
+        }
+    }
+
+    Ok(Document {
+        permalink,
+        counter: counts.lemmas,
+        missing: counts.missing,
+        metadata,
+    })
+}
+
+/// Article body with front matter and markup removed.
+pub fn clean_body(article: &str, options: &Options) -> Result<String, Error> {
+    let (_, body) = front_matter::split(article)?;
+    Ok(clean_up(body, options))
+}
+
+/// The `permalink` front matter field, or the fallback id when there's none.
+pub fn id(
+    metadata: &Map<String, Value>,
+    path: &Path,
+    fallback: FallbackId,
+) -> Result<String, Error> {
+    let field = |name: &str| metadata.get(name).and_then(Value::as_str);
+    if let Some(permalink) = field("permalink") {
+        return Ok(permalink.to_string());
+    }
+    let id = match fallback {
+        FallbackId::Slug => field("slug").map(str::to_string),
+        FallbackId::FileSlug => None,
+        FallbackId::Path => Some(path.display().to_string()),
+    };
+    id.or_else(|| file_slug(path)).ok_or_else(|| {
+        format!(
+            "Missing permalink and no fallback id for {}",
+            path.display()
+        )
+        .into()
+    })
+}
+
+/// Lowercase file name without extension with runs of other characters than
+/// letters and digits replaced by `-`.
+fn file_slug(path: &Path) -> Option<String> {
+    let mut name = path.file_stem()?;
+    if name == "index" {
+        name = path.parent()?.file_name()?;
+    }
+    let slug = name
+        .to_string_lossy()
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-");
+    Some(slug).filter(|slug| !slug.is_empty())
+}
+
+/// Prose of a Markdown body without code and markup.
+///
+/// Headings and link texts are only kept when their weight isn't 0. Fields start
+/// with their own line break, or a space for a link in the middle of a sentence,
+/// so whatever follows the last word of a field is left out.
+pub fn clean_up(body: &str, options: &Options) -> String {
+    let prose = markdown::prose(body, options.alt_text);
+    let mut text = String::new();
+    for field in [Field::Headings, Field::Body, Field::LinkText] {
+        if options.weights.get(field) > 0 {
+            let field = prose.get(field).unwrap_or_default();
+            text.push_str(field.trim_end_matches(|c: char| !c.is_alphanumeric()));
+        }
+    }
+    text
+}
@@ -0,0 +1,142 @@
+use serde_json::{Map, Value};
+use std::fmt;
+use std::str::FromStr;
+
+/// Part of a post whose words can be weighted on their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Field {
+    /// The `title` front matter field.
+    Title,
+    /// The `description` front matter field.
+    Description,
+    /// The `tags` and `categories` front matter fields.
+    Tags,
+    /// Text of Markdown headings.
+    Headings,
+    /// Prose outside of headings and links, including image alt texts.
+    Body,
+    /// Text of Markdown links, except autolinks.
+    LinkText,
+}
+
+impl Field {
+    pub const ALL: [Field; 6] = [
+        Field::Title,
+        Field::Description,
+        Field::Tags,
+        Field::Headings,
+        Field::Body,
+        Field::LinkText,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Field::Title => "title",
+            Field::Description => "description",
+            Field::Tags => "tags",
+            Field::Headings => "headings",
+            Field::Body => "body",
+            Field::LinkText => "link-text",
+        }
+    }
+
+    /// Text of a front matter field, `None` for fields of the body.
+    pub(super) fn metadata_text(self, metadata: &Map<String, Value>) -> Option<String> {
+        let names: &[&str] = match self {
+            Field::Title => &["title"],
+            Field::Description => &["description"],
+            Field::Tags => &["tags", "categories"],
+            Field::Headings | Field::Body | Field::LinkText => return None,
+        };
+        let mut text = String::new();
+        for value in names.iter().filter_map(|name| metadata.get(*name)) {
+            push_text(&mut text, value);
+        }
+        Some(text)
+    }
+}
+
+/// Strings of a front matter value and its arrays, separated by newlines.
+fn push_text(text: &mut String, value: &Value) {
+    match value {
+        Value::Null => {}
+        Value::String(string) => {
+            text.push_str(string);
+            text.push('\n');
+        }
+        Value::Array(values) => values.iter().for_each(|value| push_text(text, value)),
+        value => {
+            text.push_str(&value.to_string());
+            text.push('\n');
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Field::ALL
+            .iter()
+            .copied()
+            .find(|field| field.as_str() == s)
+            .ok_or_else(|| format!("Unknown field `{}`", s))
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How many times a word counts depending on the field it occurs in.
+///
+/// Words of a field weighted 0 are left out. By default only the body, headings
+/// and link texts count, once per occurrence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldWeights {
+    pub title: u32,
+    pub description: u32,
+    pub tags: u32,
+    pub headings: u32,
+    pub body: u32,
+    pub link_text: u32,
+}
+
+impl Default for FieldWeights {
+    fn default() -> Self {
+        FieldWeights {
+            title: 0,
+            description: 0,
+            tags: 0,
+            headings: 1,
+            body: 1,
+            link_text: 1,
+        }
+    }
+}
+
+impl FieldWeights {
+    pub fn get(&self, field: Field) -> u32 {
+        match field {
+            Field::Title => self.title,
+            Field::Description => self.description,
+            Field::Tags => self.tags,
+            Field::Headings => self.headings,
+            Field::Body => self.body,
+            Field::LinkText => self.link_text,
+        }
+    }
+
+    pub fn set(&mut self, field: Field, weight: u32) {
+        match field {
+            Field::Title => self.title = weight,
+            Field::Description => self.description = weight,
+            Field::Tags => self.tags = weight,
+            Field::Headings => self.headings = weight,
+            Field::Body => self.body = weight,
+            Field::LinkText => self.link_text = weight,
+        }
+    }
+}
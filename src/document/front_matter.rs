@@ -0,0 +1,111 @@
+//! Front matter at the start of a post, either YAML between `---` lines or TOML
+//! between `+++` lines.
+
+use serde_json::{Map, Number, Value};
+use yaml_rust2::{Yaml, YamlLoader};
+
+use crate::Error;
+
+/// Splits an article into its front matter fields and the body that follows.
+///
+/// Front matter is only looked for at the very start of the article, and ends at
+/// the first closing delimiter line. An article that doesn't start with a
+/// delimiter line, or whose delimiter is never closed, has no fields and all of
+/// it is the body, so `---` rules anywhere else are left to the Markdown parser.
+/// So is an article whose YAML block isn't a map, which is taken for text
+/// between two `---` rules.
+pub fn split(article: &str) -> Result<(Map<String, Value>, &str), Error> {
+    let article = article.strip_prefix('\u{feff}').unwrap_or(article);
+    let mut lines = article.split_inclusive('\n');
+    let closing: &[&str] = match lines.next().map(str::trim_end) {
+        Some("---") => &["---", "..."],
+        Some("+++") => &["+++"],
+        _ => return Ok((Map::new(), article)),
+    };
+
+    let start = article.find('\n').map_or(article.len(), |end| end + 1);
+    let mut end = start;
+    for line in lines {
+        if closing.contains(&line.trim_end()) {
+            let contents = &article[start..end];
+            let metadata = if closing[0] == "+++" {
+                Some(parse_toml(contents)?)
+            } else {
+                parse_yaml(contents)?
+            };
+            return Ok(match metadata {
+                Some(metadata) => (metadata, &article[end + line.len()..]),
+                None => (Map::new(), article),
+            });
+        }
+        end += line.len();
+    }
+    Ok((Map::new(), article))
+}
+
+/// Fields of a YAML block, `None` when it holds something other than a map.
+fn parse_yaml(contents: &str) -> Result<Option<Map<String, Value>>, Error> {
+    let document = YamlLoader::load_from_str(contents)?.into_iter().next();
+    Ok(match document.map(yaml_to_json) {
+        Some(Value::Object(metadata)) => Some(metadata),
+        None | Some(Value::Null) => Some(Map::new()),
+        Some(_) => None,
+    })
+}
+
+/// Dates are kept as they are written, and so are floats that JSON can't hold.
+fn yaml_to_json(value: Yaml) -> Value {
+    match value {
+        Yaml::String(string) => Value::String(string),
+        Yaml::Integer(integer) => Value::from(integer),
+        Yaml::Real(real) => real
+            .parse()
+            .ok()
+            .and_then(Number::from_f64)
+            .map_or(Value::String(real), Value::Number),
+        Yaml::Boolean(boolean) => Value::Bool(boolean),
+        Yaml::Array(array) => Value::Array(array.into_iter().map(yaml_to_json).collect()),
+        Yaml::Hash(hash) => Value::Object(
+            hash.into_iter()
+                .filter_map(|(key, value)| Some((yaml_key(key)?, yaml_to_json(value))))
+                .collect(),
+        ),
+        Yaml::Alias(_) | Yaml::Null | Yaml::BadValue => Value::Null,
+    }
+}
+
+/// Scalar keys as written, other keys can't be JSON keys and are left out.
+fn yaml_key(key: Yaml) -> Option<String> {
+    match key {
+        Yaml::String(string) | Yaml::Real(string) => Some(string),
+        Yaml::Integer(integer) => Some(integer.to_string()),
+        Yaml::Boolean(boolean) => Some(boolean.to_string()),
+        _ => None,
+    }
+}
+
+fn parse_toml(contents: &str) -> Result<Map<String, Value>, Error> {
+    let table = contents.parse::<toml::Table>()?;
+    Ok(table
+        .into_iter()
+        .map(|(key, value)| (key, toml_to_json(value)))
+        .collect())
+}
+
+/// Dates are kept as they are written.
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(string) => Value::String(string),
+        toml::Value::Integer(integer) => Value::from(integer),
+        toml::Value::Float(float) => Number::from_f64(float).map_or(Value::Null, Value::Number),
+        toml::Value::Boolean(boolean) => Value::Bool(boolean),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(array) => Value::Array(array.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
@@ -0,0 +1,160 @@
+//! Prose of a Markdown body, parsed as CommonMark with tables, footnotes,
+//! strikethrough and task lists.
+
+use pulldown_cmark::{Event, LinkType, Options, Parser, Tag, TagEnd};
+use regex::Regex;
+use std::sync::LazyLock;
+
+use super::Field;
+use crate::tokenizer::is_sentence_break;
+
+static HTML_TAG_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+
+/// Text nodes of a Markdown body without code and HTML tags, by field.
+#[derive(Debug, Clone, Default)]
+pub struct Prose {
+    pub body: String,
+    pub headings: String,
+    pub link_text: String,
+}
+
+impl Prose {
+    pub fn get(&self, field: Field) -> Option<&str> {
+        match field {
+            Field::Body => Some(&self.body),
+            Field::Headings => Some(&self.headings),
+            Field::LinkText => Some(&self.link_text),
+            Field::Title | Field::Description | Field::Tags => None,
+        }
+    }
+
+    fn field(&mut self, spans: &[Span], in_heading: bool) -> &mut String {
+        if spans.contains(&Span::Link) {
+            &mut self.link_text
+        } else {
+            self.block_field(in_heading)
+        }
+    }
+
+    /// Field of the text around links.
+    fn block_field(&mut self, in_heading: bool) -> &mut String {
+        if in_heading {
+            &mut self.headings
+        } else {
+            &mut self.body
+        }
+    }
+
+    /// Adds a line break, which also ends a sentence, to the body and headings.
+    ///
+    /// Link texts never span blocks, they start as the sentence around them goes.
+    fn end_block(&mut self) {
+        self.body.push('\n');
+        self.headings.push('\n');
+    }
+
+    /// Separates a link text from the text around it, starting a sentence in the
+    /// link texts only when the link starts one.
+    fn start_link(&mut self, in_heading: bool) {
+        let around = self.block_field(in_heading);
+        let separator = if ends_sentence(around) { '\n' } else { ' ' };
+        around.push(' ');
+        self.link_text.push(separator);
+    }
+
+    fn end_link(&mut self, in_heading: bool) {
+        self.block_field(in_heading).push(' ');
+        self.link_text.push(' ');
+    }
+}
+
+/// Whether a word added to `text` would start a sentence.
+fn ends_sentence(text: &str) -> bool {
+    let words = text.trim_end_matches(|c: char| !c.is_alphanumeric());
+    words.is_empty() || text[words.len()..].contains(is_sentence_break)
+}
+
+/// An open element whose text needs special handling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Span {
+    /// Code block, autolink or image whose alt text is left out.
+    Hidden,
+    Link,
+    Image,
+}
+
+/// Splits the prose of `body` into headings, link texts and the rest.
+///
+/// Image alt texts are only kept when asked for, as part of the field they occur
+/// in. Autolinks are always left out, as their text is the URL itself.
+pub fn prose(body: &str, alt_text: bool) -> Prose {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+
+    let mut prose = Prose::default();
+    let mut spans = Vec::new();
+    let mut in_heading = false;
+    for event in Parser::new_ext(body, options) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => spans.push(Span::Hidden),
+            Event::Start(Tag::Link { link_type, .. }) => {
+                prose.start_link(in_heading);
+                spans.push(match link_type {
+                    LinkType::Autolink | LinkType::Email => Span::Hidden,
+                    _ => Span::Link,
+                });
+            }
+            Event::Start(Tag::Image { .. }) => {
+                spans.push(if alt_text { Span::Image } else { Span::Hidden })
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                prose.end_block();
+                spans.pop();
+            }
+            Event::End(TagEnd::Link) => {
+                spans.pop();
+                prose.end_link(in_heading);
+            }
+            Event::End(TagEnd::Image) => {
+                prose.field(&spans, in_heading).push(' ');
+                spans.pop();
+            }
+            Event::Start(Tag::Heading { .. }) => {
+                prose.end_block();
+                in_heading = true;
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                prose.end_block();
+                in_heading = false;
+            }
+            Event::Text(_) | Event::Html(_) if spans.contains(&Span::Hidden) => {}
+            Event::Text(content) => prose.field(&spans, in_heading).push_str(&content),
+            Event::Html(html) => {
+                let text = prose.field(&spans, in_heading);
+                text.push(' ');
+                text.push_str(&HTML_TAG_PATTERN.replace_all(&html, " "));
+            }
+            Event::Start(
+                Tag::Emphasis
+                | Tag::Strong
+                | Tag::Strikethrough
+                | Tag::Superscript
+                | Tag::Subscript,
+            )
+            | Event::End(
+                TagEnd::Emphasis
+                | TagEnd::Strong
+                | TagEnd::Strikethrough
+                | TagEnd::Superscript
+                | TagEnd::Subscript,
+            ) => {}
+            Event::Start(_) | Event::End(_) | Event::Rule => prose.end_block(),
+            // Inline code, math, inline HTML tags, footnote references and breaks
+            // all separate words.
+            _ => prose.field(&spans, in_heading).push(' '),
+        }
+    }
+    prose
+}
@@ -0,0 +1,190 @@
+//! Guesses lemmas of words missing from the dictionary from their endings.
+//!
+//! Every dictionary form is turned into a rule that strips some characters from its
+//! end and appends others, e.g. `domu → dom` strips `u` and appends nothing. Rules
+//! are counted per word ending, and an unknown word gets the most common rule of the
+//! longest ending it shares with the dictionary.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use crate::{Dictionary, Error};
+
+/// Longest ending, in characters, that rules are indexed by.
+const MAX_ENDING: usize = 7;
+
+/// Endings seen fewer times than this are too rare to generalise from.
+const MIN_SUPPORT: u32 = 3;
+
+/// Characters a guess must keep from the original word.
+const MIN_STEM: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Rule {
+    /// Number of characters removed from the end of the form.
+    strip: usize,
+    append: String,
+}
+
+#[derive(Debug, Clone)]
+struct Suggestion {
+    rule: Rule,
+    /// Forms with this ending that follow the rule.
+    count: u32,
+    /// Forms with this ending.
+    total: u32,
+}
+
+/// A lemma proposed by the [`Guesser`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Guess {
+    pub lemma: String,
+    /// Share of dictionary forms with the same ending that follow the same rule.
+    pub confidence: f32,
+}
+
+#[derive(Debug, Default)]
+pub struct Guesser {
+    endings: HashMap<String, Suggestion>,
+}
+
+impl Guesser {
+    /// Learns ending rules from every form of the dictionary.
+    pub fn train(dictionary: &Dictionary) -> Self {
+        let mut rules: HashMap<Rule, u32> = HashMap::new();
+        let mut counts: HashMap<(String, u32), u32> = HashMap::new();
+
+        dictionary.for_each_form(|form, entries| {
+            let form = form.to_lowercase();
+            let chars = form.chars().collect::<Vec<char>>();
+            if !chars.iter().all(|c| c.is_alphabetic()) {
+                return;
+            }
+
+            let mut seen = Vec::new();
+            for entry in entries {
+                let rule = rule_for(&chars, &entry.lemma.to_lowercase());
+                if seen.contains(&rule) {
+                    continue;
+                }
+                let next_id = rules.len() as u32;
+                let id = *rules.entry(rule.clone()).or_insert(next_id);
+                for length in rule.strip.max(1)..=MAX_ENDING.min(chars.len()) {
+                    let ending = chars[chars.len() - length..].iter().collect::<String>();
+                    *counts.entry((ending, id)).or_insert(0) += 1;
+                }
+                seen.push(rule);
+            }
+        });
+
+        let rules_by_id = rules
+            .into_iter()
+            .map(|(rule, id)| (id, rule))
+            .collect::<HashMap<u32, Rule>>();
+
+        let mut endings: HashMap<String, Suggestion> = HashMap::new();
+        for ((ending, id), count) in counts {
+            let rule = &rules_by_id[&id];
+            let suggestion = endings.entry(ending).or_insert_with(|| Suggestion {
+                rule: rule.clone(),
+                count: 0,
+                total: 0,
+            });
+            suggestion.total += count;
+            if (count, &suggestion.rule) > (suggestion.count, rule) {
+                suggestion.rule = rule.clone();
+                suggestion.count = count;
+            }
+        }
+        endings.retain(|_, suggestion| suggestion.total >= MIN_SUPPORT);
+
+        Guesser { endings }
+    }
+
+    /// Reads rules written by [`Guesser::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        let mut endings = HashMap::new();
+        for (number, line) in file.lines().enumerate() {
+            let line = line?;
+            let columns = line.split('\t').collect::<Vec<&str>>();
+            let invalid = || format!("Invalid guesser rule on line {}", number + 1);
+            if columns.len() != 5 {
+                return Err(invalid().into());
+            }
+            let suggestion = Suggestion {
+                rule: Rule {
+                    strip: columns[1].parse().map_err(|_| invalid())?,
+                    append: columns[2].to_string(),
+                },
+                count: columns[3].parse().map_err(|_| invalid())?,
+                total: columns[4].parse().map_err(|_| invalid())?,
+            };
+            endings.insert(columns[0].to_string(), suggestion);
+        }
+        Ok(Guesser { endings })
+    }
+
+    /// Writes the rules as `ending\tstrip\tappend\tcount\ttotal` lines, sorted by ending.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let sorted = self.endings.iter().collect::<BTreeMap<_, _>>();
+        for (ending, suggestion) in sorted {
+            writeln!(
+                file,
+                "{}\t{}\t{}\t{}\t{}",
+                ending,
+                suggestion.rule.strip,
+                suggestion.rule.append,
+                suggestion.count,
+                suggestion.total
+            )?;
+        }
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Guesses the lemma of a lowercased word from its longest known ending.
+    pub fn guess(&self, word: &str) -> Option<Guess> {
+        let chars = word.chars().collect::<Vec<char>>();
+        (1..=MAX_ENDING.min(chars.len())).rev().find_map(|length| {
+            let ending = chars[chars.len() - length..].iter().collect::<String>();
+            let suggestion = self.endings.get(&ending)?;
+            if chars.len() < suggestion.rule.strip + MIN_STEM {
+                return None;
+            }
+            let mut lemma = chars[..chars.len() - suggestion.rule.strip]
+                .iter()
+                .collect::<String>();
+            lemma.push_str(&suggestion.rule.append);
+            Some(Guess {
+                lemma,
+                confidence: suggestion.count as f32 / suggestion.total as f32,
+            })
+        })
+    }
+
+    /// Number of indexed endings.
+    pub fn len(&self) -> usize {
+        self.endings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.endings.is_empty()
+    }
+}
+
+/// Rule turning `form` into `lemma` after their common prefix.
+fn rule_for(form: &[char], lemma: &str) -> Rule {
+    let lemma = lemma.chars().collect::<Vec<char>>();
+    let common = form
+        .iter()
+        .zip(lemma.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    Rule {
+        strip: form.len() - common,
+        append: lemma[common..].iter().collect(),
+    }
+}
@@ -0,0 +1,605 @@
+use rayon::{join, prelude::*};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
+use std::path::Path;
+
+use crate::dictionary::{fold, Dictionary, Entry, FoldedIndex};
+use crate::guesser::{Guess, Guesser};
+use crate::tags::{PartOfSpeech, Tag};
+use crate::tokenizer::{self, TokenKind, Tokenizer};
+use crate::Error;
+
+/// How to pick a single lemma for a word form with several candidates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Disambiguation {
+    /// Readings as prepositions, conjunctions, particles, adverbs and personal
+    /// pronouns first, imperatives, vocatives and abbreviations last, so `tak`
+    /// stays `tak` and `mam` is `mieć` rather than `mamić`. Ties go to the lemma
+    /// with the most entries in the dictionary, then to a capitalized lemma equal
+    /// to the form, such as `Ala`.
+    #[default]
+    ReadingPriority,
+    /// The lemma with the most entries in the dictionary.
+    MostForms,
+    /// Like [`Disambiguation::ReadingPriority`], with ties going to the lemma seen
+    /// most often as the only reading of some word in the corpus first, see
+    /// [`Lemmatizer::count_votes`].
+    CorpusVotes,
+}
+
+/// How hyphenated compounds missing from the dictionary, such as
+/// `polsko-niemieckiej`, are lemmatized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compounds {
+    /// Every part lemmatized and counted on its own, as if the hyphens were spaces.
+    #[default]
+    Parts,
+    /// Only looked up as a whole, like any other word.
+    Whole,
+    /// Every part lemmatized on its own and joined back with hyphens, as in
+    /// `polsko-niemiecki`.
+    Join,
+    /// Like [`Compounds::Join`], with the lemma of every part also counted on its own.
+    JoinAndParts,
+}
+
+/// Whether the capitalisation of a word decides how it's looked up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Casing {
+    /// Every word is lowercased before it's looked up.
+    #[default]
+    Ignore,
+    /// Words capitalized mid-sentence are looked up as written first, so `Polska`
+    /// is the country rather than the adjective `polski`. Words starting a sentence
+    /// are looked up lowercased first. Lemmas are still counted lowercased.
+    Lookup,
+    /// Like [`Casing::Lookup`], with lemmas of proper nouns counted as written, as
+    /// `Polska` or `Java`, apart from common words. Words missing from the
+    /// dictionary that are capitalized mid-sentence are kept as written too.
+    ProperNouns,
+}
+
+/// A lemmatized word.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token<'a> {
+    pub form: &'a str,
+    /// `None` for words missing from the dictionary.
+    pub lemma: Option<&'a str>,
+    /// Interpretations of the form that belong to the chosen lemma.
+    pub tags: Vec<Tag>,
+    /// Lemma proposed by the guesser for words missing from the dictionary.
+    pub guess: Option<Guess>,
+}
+
+/// Result of [`Lemmatizer::count_words`].
+#[derive(Debug, Clone, Default)]
+pub struct WordCounts {
+    pub lemmas: HashMap<String, u32>,
+    /// Occurrences of words missing from the dictionary, by form.
+    pub missing: HashMap<String, u32>,
+}
+
+/// Dictionary of word forms together with the list of stopwords.
+pub struct Lemmatizer {
+    dictionary: Dictionary,
+    stopwords: HashSet<String>,
+    disambiguation: Disambiguation,
+    votes: HashMap<String, u32>,
+    parts_of_speech: Option<HashSet<PartOfSpeech>>,
+    guesser: Option<Guesser>,
+    min_confidence: f32,
+    tokenizer: Tokenizer,
+    compounds: Compounds,
+    folded: Option<FoldedIndex>,
+    casing: Casing,
+}
+
+impl Lemmatizer {
+    pub fn new(dictionary: Dictionary, stopwords: HashSet<String>) -> Self {
+        Lemmatizer {
+            dictionary,
+            stopwords,
+            disambiguation: Disambiguation::default(),
+            votes: HashMap::new(),
+            parts_of_speech: None,
+            guesser: None,
+            min_confidence: 0.0,
+            tokenizer: Tokenizer::default(),
+            compounds: Compounds::default(),
+            folded: None,
+            casing: Casing::default(),
+        }
+    }
+
+    /// Loads the brotli-compressed dictionary and the stopwords file in parallel.
+    pub fn load(
+        dictionary_path: impl AsRef<Path> + Send,
+        stopwords_path: impl AsRef<Path> + Send,
+    ) -> Result<Self, Error> {
+        let (dictionary, stopwords) = join(
+            || Dictionary::load(dictionary_path),
+            || build_stopwords(stopwords_path),
+        );
+        Ok(Lemmatizer::new(dictionary?, stopwords?))
+    }
+
+    pub fn with_disambiguation(mut self, disambiguation: Disambiguation) -> Self {
+        self.disambiguation = disambiguation;
+        self
+    }
+
+    /// Only counts words that have a reading with one of the given parts of speech.
+    ///
+    /// Words missing from the dictionary are still counted, since their part of
+    /// speech is unknown.
+    pub fn with_parts_of_speech(mut self, parts_of_speech: HashSet<PartOfSpeech>) -> Self {
+        self.parts_of_speech = Some(parts_of_speech);
+        self
+    }
+
+    /// Falls back to guessing lemmas of unknown words from their endings, keeping
+    /// guesses with at least `min_confidence`.
+    pub fn with_guesser(mut self, guesser: Guesser, min_confidence: f32) -> Self {
+        self.guesser = Some(guesser);
+        self.min_confidence = min_confidence;
+        self
+    }
+
+    /// Splits text into words with the given tokenizer instead of the default one.
+    pub fn with_tokenizer(mut self, tokenizer: Tokenizer) -> Self {
+        self.tokenizer = tokenizer;
+        self
+    }
+
+    /// Sets how hyphenated compounds missing from the dictionary are lemmatized.
+    pub fn with_compounds(mut self, compounds: Compounds) -> Self {
+        self.compounds = compounds;
+        self
+    }
+
+    /// Looks up words missing from the dictionary by their spelling without
+    /// diacritics, so `zolw` is lemmatized as `żółw`.
+    pub fn with_diacritics_folding(mut self) -> Self {
+        self.folded = Some(FoldedIndex::new(&self.dictionary));
+        self
+    }
+
+    /// Sets whether the capitalisation of a word decides how it's looked up.
+    pub fn with_casing(mut self, casing: Casing) -> Self {
+        self.casing = casing;
+        self
+    }
+
+    /// Sets lemma votes gathered over a corpus with [`Lemmatizer::count_votes`].
+    pub fn set_votes(&mut self, votes: HashMap<String, u32>) {
+        self.votes = votes;
+    }
+
+    /// Returns the lemma for a word form, if the dictionary knows it.
+    ///
+    /// Ambiguous forms are resolved according to the [`Disambiguation`] policy;
+    /// remaining ties go to the alphabetically first lemma.
+    pub fn lemmatize(&self, word: &str) -> Option<&str> {
+        self.choose(word).map(|entry| entry.lemma)
+    }
+
+    /// The reading of a word form whose lemma is the most likely.
+    fn choose(&self, word: &str) -> Option<Entry<'_>> {
+        let candidates = self.candidates(word);
+        let first = *candidates.first()?;
+        if candidates.iter().all(|entry| entry.lemma == first.lemma) {
+            return Some(first);
+        }
+
+        candidates
+            .into_iter()
+            .rev()
+            .max_by_key(|entry| self.rank(word, *entry))
+    }
+
+    /// How likely the lemma of a reading of `word` is, according to the
+    /// [`Disambiguation`] policy.
+    fn rank(&self, word: &str, entry: Entry) -> (u8, Option<u32>, u32, bool) {
+        let (priority, votes) = match self.disambiguation {
+            Disambiguation::ReadingPriority => (reading_priority(entry.raw_tags), None),
+            Disambiguation::CorpusVotes => (
+                reading_priority(entry.raw_tags),
+                self.votes.get(entry.lemma).copied(),
+            ),
+            Disambiguation::MostForms => (0, None),
+        };
+        let paradigm_size = self.dictionary.paradigm_size(entry.lemma);
+        // A name equal to the form only wins over a paradigm as big as its own, as
+        // `Jana` is rather the genitive of `Jan` and `Polski` that of `Polska`.
+        let is_name = self.disambiguation != Disambiguation::MostForms
+            && entry.lemma == word
+            && word.starts_with(char::is_uppercase);
+        (priority, votes, paradigm_size, is_name)
+    }
+
+    /// Guesses the lemma of a word missing from the dictionary, if a guesser is set
+    /// and it is confident enough.
+    pub fn guess(&self, word: &str) -> Option<Guess> {
+        self.guesser
+            .as_ref()?
+            .guess(word)
+            .filter(|guess| guess.confidence >= self.min_confidence)
+    }
+
+    /// Lemmatizes a word form and attaches the tags of the chosen lemma.
+    pub fn token<'a>(&'a self, word: &'a str) -> Token<'a> {
+        let lemma = self.lemmatize(word);
+        let tags = self.readings(word, lemma).flat_map(Entry::tags).collect();
+        let guess = match lemma {
+            Some(_) => None,
+            None => self.guess(word),
+        };
+        Token {
+            form: word,
+            lemma,
+            tags,
+            guess,
+        }
+    }
+
+    fn readings<'a>(
+        &'a self,
+        word: &str,
+        lemma: Option<&'a str>,
+    ) -> impl Iterator<Item = Entry<'a>> + 'a {
+        self.candidates(word)
+            .into_iter()
+            .filter(move |entry| Some(entry.lemma) == lemma)
+    }
+
+    fn has_allowed_part_of_speech(&self, word: &str, lemma: &str) -> bool {
+        match &self.parts_of_speech {
+            None => true,
+            Some(allowed) => self
+                .readings(word, Some(lemma))
+                .flat_map(Entry::parts_of_speech)
+                .any(|pos| allowed.contains(&pos)),
+        }
+    }
+
+    /// All dictionary readings of a word form.
+    ///
+    /// With diacritics folding, a form missing from the dictionary gets the
+    /// readings of its most likely spelling with diacritics.
+    pub fn candidates(&self, word: &str) -> Vec<Entry<'_>> {
+        let candidates = self.dictionary.candidates(word);
+        if !candidates.is_empty() || self.folded.is_none() {
+            return candidates;
+        }
+        match self.restore(word).first() {
+            Some(form) => self.dictionary.candidates(form),
+            None => candidates,
+        }
+    }
+
+    /// Dictionary spellings of a lowercased word that differ from it at most in
+    /// diacritics, most likely first.
+    ///
+    /// Spellings are ranked by their lemma, like ambiguous forms. Without
+    /// [`Lemmatizer::with_diacritics_folding`] only the word itself is suggested,
+    /// when the dictionary knows it.
+    pub fn restore(&self, word: &str) -> Vec<String> {
+        let folded = fold(word);
+        let mut forms = match &self.folded {
+            Some(index) => index
+                .forms(&folded)
+                .iter()
+                .map(|form| form.to_string())
+                .collect(),
+            None => Vec::new(),
+        };
+        let plain = if self.folded.is_some() {
+            folded
+        } else {
+            word.to_string()
+        };
+        if !self.dictionary.candidates(&plain).is_empty() {
+            forms.push(plain);
+        }
+        forms.sort_by_cached_key(|form| {
+            let entry = self.choose(form);
+            (
+                Reverse(entry.map(|entry| self.rank(form, entry))),
+                form.clone(),
+            )
+        });
+        forms
+    }
+
+    /// Replaces words of a text missing from the dictionary with their most likely
+    /// spelling with diacritics, keeping their capitalisation.
+    pub fn restore_text(&self, text: &str) -> String {
+        let mut restored = String::with_capacity(text.len());
+        let mut end = 0;
+        for token in self.tokenizer.tokenize(text) {
+            let word = token.text.to_lowercase();
+            if !token.kind.is_word() || !self.dictionary.candidates(&word).is_empty() {
+                continue;
+            }
+            if let Some(form) = self.restore(&word).first() {
+                restored.push_str(&text[end..token.bytes.start]);
+                restored.push_str(&match_case(token.text, form));
+                end = token.bytes.end;
+            }
+        }
+        restored.push_str(&text[end..]);
+        restored
+    }
+
+    pub fn is_stopword(&self, word: &str) -> bool {
+        self.stopwords.contains(word)
+    }
+
+    pub fn dictionary(&self) -> &Dictionary {
+        &self.dictionary
+    }
+
+    pub fn stopwords(&self) -> &HashSet<String> {
+        &self.stopwords
+    }
+
+    pub fn tokenizer(&self) -> &Tokenizer {
+        &self.tokenizer
+    }
+
+    /// Spelling a word token is looked up by.
+    ///
+    /// Unless casing is [`Casing::Ignore`], a capitalized word is kept as written, or
+    /// with only its first letter a capital, when the dictionary knows that spelling.
+    /// Words starting a sentence and words in capitals, as in `Kot` or `POLSKA`,
+    /// are only kept so when their lowercased spelling is unknown. With
+    /// [`Casing::ProperNouns`], words capitalized mid-sentence are kept as written
+    /// too when the dictionary knows no spelling of them. Other words are
+    /// lowercased.
+    pub fn lookup_form(&self, token: &tokenizer::Token) -> String {
+        let lowercase = token.text.to_lowercase();
+        if self.casing == Casing::Ignore || !token.is_capitalized() {
+            return lowercase;
+        }
+        let known = |form: &str| !self.dictionary.candidates(form).is_empty();
+        let in_capitals = token
+            .text
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .nth(1)
+            .is_some()
+            && !token.text.contains(char::is_lowercase);
+        if (token.sentence_start || in_capitals) && known(&lowercase) {
+            return lowercase;
+        }
+        let capitalized = capitalize(&lowercase);
+        if known(token.text) {
+            token.text.to_string()
+        } else if known(&capitalized) {
+            capitalized
+        } else if self.casing == Casing::ProperNouns && !token.sentence_start && !known(&lowercase)
+        {
+            token.text.to_string()
+        } else {
+            lowercase
+        }
+    }
+
+    /// Lowercased words of a text, leaving out numbers, URLs and email addresses.
+    pub fn words(&self, text: &str) -> Vec<String> {
+        self.tokenizer
+            .tokenize(text)
+            .into_iter()
+            .filter(|token| token.kind.is_word())
+            .map(|token| token.text.to_lowercase())
+            .collect()
+    }
+
+    /// Counts lemmas in a text without markup.
+    ///
+    /// Words missing from the dictionary are counted under their own form, or under
+    /// the guessed lemma, and are also reported in [`WordCounts::missing`].
+    pub fn count_words(&self, text: &str) -> WordCounts {
+        let mut counts = WordCounts::default();
+        self.add_words(text, 1, &mut counts);
+        counts
+    }
+
+    /// Adds the lemmas in a text without markup to `counts`, each occurrence
+    /// counted `weight` times.
+    ///
+    /// Missing words are reported once per occurrence whatever the weight, and so
+    /// are compounds with a part missing from the dictionary. Compounds lemmatized
+    /// part by part are counted whatever their parts of speech. Words are looked up
+    /// by their [`Lemmatizer::lookup_form`].
+    pub fn add_words(&self, text: &str, weight: u32, counts: &mut WordCounts) {
+        for token in self.tokenizer.tokenize(text) {
+            if !token.kind.is_word() {
+                continue;
+            }
+            let word = self.lookup_form(&token);
+            let lemma = self.compound_lemma(&word);
+            let split = self.compounds == Compounds::Parts
+                && token.kind == TokenKind::Compound
+                && self.lemmatize(&word).is_none();
+            if lemma.is_none() && !split {
+                self.add_word(&word, weight, counts, true);
+                continue;
+            }
+            if self.is_ignored(&word) {
+                continue;
+            }
+
+            // A compound with unknown parts is reported missing once, as a whole.
+            let parts = word.split(tokenizer::is_hyphen);
+            if parts.clone().any(|part| self.lemmatize(part).is_none()) {
+                *counts.missing.entry(word.clone()).or_insert(0) += 1;
+            }
+            if split || self.compounds == Compounds::JoinAndParts {
+                for part in parts {
+                    self.add_word(part, weight, counts, false);
+                }
+            }
+            if let Some(lemma) = lemma {
+                *counts.lemmas.entry(lemma).or_insert(0) += weight;
+            }
+        }
+    }
+
+    /// Whether a word is too short or a stopword to be counted.
+    fn is_ignored(&self, w: &str) -> bool {
+        w.len() <= 1
+            || self.is_stopword(w)
+            || w.contains(char::is_uppercase) && self.is_stopword(&w.to_lowercase())
+    }
+
+    /// Counts a word as spelled by [`Lemmatizer::lookup_form`], so capitals only
+    /// remain in proper nouns, and reports it when it's unknown and `report_missing`.
+    fn add_word(&self, w: &str, weight: u32, counts: &mut WordCounts, report_missing: bool) {
+        if self.is_ignored(w) {
+            return;
+        }
+        let is_lowercase = !w.contains(char::is_uppercase);
+        let lemma = match self.lemmatize(w) {
+            Some(lemma) if self.has_allowed_part_of_speech(w, lemma) => lemma.to_owned(),
+            Some(_) => return,
+            None => {
+                if report_missing {
+                    *counts.missing.entry(w.to_string()).or_insert(0) += 1;
+                }
+                match self.guess(w) {
+                    Some(guess) if is_lowercase => guess.lemma,
+                    _ => w.to_string(),
+                }
+            }
+        };
+        let lemma = match self.casing {
+            Casing::Lookup => lemma.to_lowercase(),
+            Casing::Ignore | Casing::ProperNouns => lemma,
+        };
+        *counts.lemmas.entry(lemma).or_insert(0) += weight;
+    }
+
+    /// Lemmas of the parts of a lowercased hyphenated compound joined with hyphens.
+    ///
+    /// `None` unless compounds are joined, or when the dictionary knows the
+    /// compound itself. Ad-adjectival forms such as `biało` in
+    /// `biało-czerwonej` are kept as they are, and so are parts missing from the
+    /// dictionary that can't be guessed.
+    pub fn compound_lemma(&self, word: &str) -> Option<String> {
+        if !matches!(self.compounds, Compounds::Join | Compounds::JoinAndParts)
+            || tokenizer::classify(word) != TokenKind::Compound
+            || self.lemmatize(word).is_some()
+        {
+            return None;
+        }
+        let is_ad_adjectival = |part: &str| {
+            self.dictionary
+                .candidates(part)
+                .iter()
+                .flat_map(|entry| entry.parts_of_speech())
+                .any(|pos| pos == PartOfSpeech::AdAdjectivalAdjective)
+        };
+        let parts = word
+            .split(tokenizer::is_hyphen)
+            .map(|part| match self.lemmatize(part) {
+                _ if is_ad_adjectival(part) => part.to_string(),
+                Some(lemma) => lemma.to_string(),
+                None => self
+                    .guess(part)
+                    .map_or_else(|| part.to_string(), |guess| guess.lemma),
+            })
+            .collect::<Vec<String>>();
+        Some(parts.join("-"))
+    }
+
+    /// Counts lemmas of the words in a text without markup that have only one
+    /// candidate, looked up by their [`Lemmatizer::lookup_form`] so proper nouns
+    /// such as `Polska` get votes too.
+    ///
+    /// Votes summed over a whole corpus drive [`Disambiguation::CorpusVotes`].
+    pub fn count_votes(&self, text: &str) -> HashMap<String, u32> {
+        let mut votes: HashMap<String, u32> = HashMap::new();
+        for token in self.tokenizer.tokenize(text) {
+            if !token.kind.is_word() {
+                continue;
+            }
+            let candidates = self.candidates(&self.lookup_form(&token));
+            if let Some(first) = candidates.first() {
+                if candidates.iter().all(|entry| entry.lemma == first.lemma) {
+                    *votes.entry(first.lemma.to_string()).or_insert(0) += 1;
+                }
+            }
+        }
+        votes
+    }
+}
+
+/// How likely a reading is whatever its lemma, from the most likely
+/// interpretation in its tag column: 2 for uninflected function words and
+/// personal pronouns, 0 for imperatives, vocatives and abbreviations, 1 otherwise.
+fn reading_priority(raw_tags: &str) -> u8 {
+    raw_tags
+        .split('+')
+        .map(|tag| {
+            let mut segments = tag.split(':');
+            let pos = segments.next().and_then(|pos| pos.parse().ok());
+            if segments.any(|segment| segment == "impt" || segment == "voc") {
+                return 0;
+            }
+            match pos {
+                Some(
+                    PartOfSpeech::Preposition
+                    | PartOfSpeech::Conjunction
+                    | PartOfSpeech::Complementizer
+                    | PartOfSpeech::Particle
+                    | PartOfSpeech::Adverb
+                    | PartOfSpeech::Predicative
+                    | PartOfSpeech::PersonalPronoun
+                    | PartOfSpeech::ReflexivePronoun,
+                ) => 2,
+                Some(PartOfSpeech::Abbreviation) => 0,
+                _ => 1,
+            }
+        })
+        .max()
+        .unwrap_or(1)
+}
+
+/// `word` written with the capitalisation of `original`: all capitals, a capital
+/// first letter, or as it is.
+fn match_case(original: &str, word: &str) -> String {
+    let mut letters = original.chars().filter(|c| c.is_alphabetic());
+    let first_upper = letters.next().is_some_and(char::is_uppercase);
+    if first_upper && letters.clone().next().is_some() && letters.all(char::is_uppercase) {
+        word.to_uppercase()
+    } else if first_upper {
+        capitalize(word)
+    } else {
+        word.to_string()
+    }
+}
+
+/// `word` with a capital first letter.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+pub fn build_stopwords(path: impl AsRef<Path>) -> Result<HashSet<String>, Error> {
+    eprintln!("Reading stopwords file…");
+    let file = std::fs::File::open(path)?;
+    let lines = std::io::BufReader::new(file)
+        .lines()
+        .collect::<Result<Vec<String>, _>>()?;
+    eprintln!("Building stopwords Set…");
+    Ok(lines
+        .par_iter()
+        .map(|line| line.trim().to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect())
+}
@@ -0,0 +1,26 @@
+//! Lemmatization of Polish text and related-posts computation.
+//!
+//! The [`Lemmatizer`] holds the form → lemmas [`Dictionary`] and the stopword list
+//! and splits text with a [`Tokenizer`], [`document`] turns Markdown articles into
+//! term counters, and [`similarity`] compares those counters with each other.
+
+pub mod dictionary;
+pub mod document;
+pub mod guesser;
+pub mod lemmatizer;
+pub mod oov;
+pub mod results;
+pub mod similarity;
+pub mod tags;
+pub mod tokenizer;
+
+pub use dictionary::Dictionary;
+pub use document::{analyze, analyze_path, Document};
+pub use guesser::{Guess, Guesser};
+pub use lemmatizer::{Casing, Compounds, Disambiguation, Lemmatizer, Token, WordCounts};
+pub use results::Results;
+pub use tags::{PartOfSpeech, Tag};
+pub use tokenizer::Tokenizer;
+
+/// Error type used across the crate.
+pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
@@ -0,0 +1,64 @@
+//! Report of words missing from the dictionary across a corpus, meant for curating
+//! a custom dictionary.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+
+use crate::{Document, Error, Guess, Lemmatizer};
+
+#[derive(Debug, Clone)]
+pub struct MissingWord {
+    pub word: String,
+    /// Occurrences in the whole corpus.
+    pub frequency: u32,
+    /// Permalinks of the documents containing the word, sorted.
+    pub documents: Vec<String>,
+    pub guess: Option<Guess>,
+}
+
+/// Aggregates missing words of all documents, most frequent first.
+pub fn report(documents: &[Document], lemmatizer: &Lemmatizer) -> Vec<MissingWord> {
+    let mut words: HashMap<&str, MissingWord> = HashMap::new();
+    for document in documents {
+        for (word, count) in &document.missing {
+            let missing = words.entry(word).or_insert_with(|| MissingWord {
+                word: word.clone(),
+                frequency: 0,
+                documents: Vec::new(),
+                guess: lemmatizer.guess(word),
+            });
+            missing.frequency += count;
+            missing.documents.push(document.permalink.clone());
+        }
+    }
+
+    let mut report = words.into_values().collect::<Vec<MissingWord>>();
+    for missing in &mut report {
+        missing.documents.sort_unstable();
+    }
+    report.sort_unstable_by(|a, b| b.frequency.cmp(&a.frequency).then(a.word.cmp(&b.word)));
+    report
+}
+
+/// Writes the report as `word\tfrequency\tguess\tdocuments` lines, documents
+/// separated by spaces and `-` standing for no guess.
+pub fn write_report(report: &[MissingWord], path: impl AsRef<Path>) -> Result<(), Error> {
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    writeln!(file, "word\tfrequency\tguess\tdocuments")?;
+    for missing in report {
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}",
+            missing.word,
+            missing.frequency,
+            missing
+                .guess
+                .as_ref()
+                .map_or("-", |guess| guess.lemma.as_str()),
+            missing.documents.join(" ")
+        )?;
+    }
+    file.flush()?;
+    Ok(())
+}
@@ -0,0 +1,76 @@
+//! Related posts written by the `related` command, described by
+//! `schema/results.schema.json`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+use crate::similarity::Match;
+use crate::Document;
+
+/// Version of the results format, bumped on incompatible changes.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// JSON Schema of [`Results`].
+pub const SCHEMA: &str = include_str!("../schema/results.schema.json");
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Results {
+    pub version: u32,
+    /// Related posts by permalink of the analyzed post.
+    pub documents: BTreeMap<String, DocumentResults>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentResults {
+    /// Best match first.
+    pub related: Vec<RelatedDocument>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelatedDocument {
+    pub permalink: String,
+    pub score: f32,
+    pub title: Option<String>,
+    pub date: Option<String>,
+    /// All front matter fields of the related post.
+    pub metadata: Map<String, Value>,
+}
+
+impl Results {
+    /// Builds results from [`crate::similarity::top_matches`] of `documents`.
+    pub fn new(documents: &[Document], matches: &[Vec<Match>]) -> Self {
+        let documents = documents
+            .iter()
+            .zip(matches)
+            .map(|(document, matches)| {
+                let related = matches
+                    .iter()
+                    .map(|m| RelatedDocument::new(&documents[m.document], m.score))
+                    .collect();
+                (document.permalink.clone(), DocumentResults { related })
+            })
+            .collect();
+        Results {
+            version: SCHEMA_VERSION,
+            documents,
+        }
+    }
+}
+
+impl RelatedDocument {
+    fn new(document: &Document, score: f32) -> Self {
+        let field = |name: &str| match document.metadata.get(name)? {
+            Value::String(value) => Some(value.clone()),
+            Value::Null => None,
+            value => Some(value.to_string()),
+        };
+        RelatedDocument {
+            permalink: document.permalink.clone(),
+            score,
+            title: field("title"),
+            date: field("date"),
+            metadata: document.metadata.clone(),
+        }
+    }
+}
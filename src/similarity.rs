@@ -0,0 +1,399 @@
+use rayon::prelude::*;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::Document;
+
+mod index;
+
+use index::{InvertedIndex, SparseVector, Vocabulary};
+
+/// How term counts of a document are turned into vector weights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Weighting {
+    /// Raw term counts.
+    #[default]
+    Counts,
+    /// Term frequency times inverse document frequency over the analyzed corpus.
+    TfIdf {
+        /// Use `1 + ln(tf)` instead of the raw count.
+        sublinear_tf: bool,
+        /// Use `ln((1 + n) / (1 + df)) + 1`, which never drops a term shared by
+        /// every document, instead of `ln(n / df)`.
+        smooth_idf: bool,
+    },
+}
+
+/// How relatedness of two documents is measured.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scoring {
+    /// Cosine similarity of weighted term vectors, symmetric and between 0 and 1.
+    Cosine(Weighting),
+    /// Okapi BM25 with every document used as a query against the others.
+    ///
+    /// Scores are not bounded nor symmetric and only rank candidates of one document.
+    Bm25 {
+        /// Term frequency saturation.
+        k1: f32,
+        /// Document length normalisation, from 0 (none) to 1 (full).
+        b: f32,
+    },
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Scoring::Cosine(Weighting::default())
+    }
+}
+
+/// Minimum score of a match worth reporting as related.
+///
+/// The default keeps every match.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Threshold {
+    /// Matches scoring less are dropped.
+    pub absolute: f32,
+    /// Matches scoring less than this fraction of the best match of the same
+    /// document are dropped, between 0 and 1.
+    pub relative: f32,
+}
+
+impl Threshold {
+    /// Drops the matches below the threshold from `matches` sorted best first.
+    pub fn retain(&self, matches: &mut Vec<Match>) {
+        let best = matches.first().map_or(0.0, |m| m.score);
+        let min_score = self.absolute.max(best * self.relative);
+        matches.retain(|m| m.score >= min_score);
+    }
+}
+
+/// A document related to another one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Match {
+    /// Index of the related document.
+    pub document: usize,
+    pub score: f32,
+}
+
+impl Eq for Match {}
+
+impl Ord for Match {
+    /// Higher scores first, earlier documents first among equal scores.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| other.document.cmp(&self.document))
+    }
+}
+
+impl PartialOrd for Match {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Scores every document against every other one.
+///
+/// Documents sharing no terms with a document score 0. This keeps every score in
+/// memory, [`top_matches`] only keeps the best ones.
+pub fn calculate_all_scores(
+    documents: &[Document],
+    scoring: Scoring,
+) -> HashMap<String, HashMap<String, f32>> {
+    let rows = score_rows(documents, scoring, |query, row| {
+        let mut row = row.to_vec();
+        add_zero_matches(&mut row, query, documents.len(), documents.len());
+        row.iter()
+            .map(|m| (documents[m.document].permalink.clone(), m.score))
+            .collect::<HashMap<String, f32>>()
+    });
+    documents
+        .iter()
+        .zip(rows)
+        .map(|(document, row)| (document.permalink.clone(), row))
+        .collect()
+}
+
+/// The at most `k` best matches of every document above `threshold`, best first,
+/// in the order of `documents`.
+///
+/// Only `k` matches per document are kept while scoring, so memory stays
+/// proportional to the number of documents times `k`. Documents sharing no terms
+/// make up the rest of the `k` with a score of 0, unless the threshold drops them.
+pub fn top_matches(
+    documents: &[Document],
+    scoring: Scoring,
+    k: usize,
+    threshold: Threshold,
+) -> Vec<Vec<Match>> {
+    score_rows(documents, scoring, |query, row| {
+        let mut heap = BinaryHeap::with_capacity(k.min(row.len()) + 1);
+        for m in row.iter().filter(|m| m.score >= threshold.absolute) {
+            heap.push(Reverse(*m));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+        let mut matches = heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(m)| m)
+            .collect();
+        if threshold.absolute <= 0.0 {
+            add_zero_matches(&mut matches, query, documents.len(), k);
+        }
+        threshold.retain(&mut matches);
+        matches
+    })
+}
+
+/// Appends matches scoring 0 for the documents missing from `row`, earlier
+/// documents first, until it holds `len` matches or every document but `query`.
+fn add_zero_matches(row: &mut Vec<Match>, query: usize, documents: usize, len: usize) {
+    if row.len() >= len {
+        return;
+    }
+    let matched = row.iter().map(|m| m.document).collect::<HashSet<usize>>();
+    let missing =
+        (0..documents).filter(|document| *document != query && !matched.contains(document));
+    for document in missing.take(len - row.len()) {
+        row.push(Match {
+            document,
+            score: 0.0,
+        });
+    }
+}
+
+/// Scores every document and hands its index and its matches with a non-zero
+/// score to `reduce`, one document at a time.
+fn score_rows<T: Send>(
+    documents: &[Document],
+    scoring: Scoring,
+    reduce: impl Fn(usize, &[Match]) -> T + Sync,
+) -> Vec<T> {
+    match scoring {
+        Scoring::Cosine(weighting) => cosine_rows(documents, weighting, reduce),
+        Scoring::Bm25 { k1, b } => bm25_rows(documents, k1, b, reduce),
+    }
+}
+
+/// Number of documents every term occurs in.
+pub fn document_frequencies(documents: &[Document]) -> HashMap<&str, u32> {
+    let mut frequencies: HashMap<&str, u32> = HashMap::new();
+    for document in documents {
+        for term in document.counter.keys() {
+            *frequencies.entry(term).or_insert(0) += 1;
+        }
+    }
+    frequencies
+}
+
+/// Weighted term vector of every document, in the order of `documents`.
+pub fn weigh(documents: &[Document], weighting: Weighting) -> Vec<HashMap<&str, f32>> {
+    match weighting {
+        Weighting::Counts => documents
+            .iter()
+            .map(|document| {
+                document
+                    .counter
+                    .iter()
+                    .map(|(term, count)| (term.as_str(), *count as f32))
+                    .collect()
+            })
+            .collect(),
+        Weighting::TfIdf {
+            sublinear_tf,
+            smooth_idf,
+        } => {
+            let n = documents.len() as f32;
+            let frequencies = document_frequencies(documents);
+            let idf = |term: &str| {
+                let df = frequencies[term] as f32;
+                if smooth_idf {
+                    ((1.0 + n) / (1.0 + df)).ln() + 1.0
+                } else {
+                    (n / df).ln()
+                }
+            };
+            documents
+                .par_iter()
+                .map(|document| {
+                    document
+                        .counter
+                        .iter()
+                        .map(|(term, count)| {
+                            let tf = if sublinear_tf {
+                                1.0 + (*count as f32).ln()
+                            } else {
+                                *count as f32
+                            };
+                            (term.as_str(), tf * idf(term))
+                        })
+                        .collect()
+                })
+                .collect()
+        }
+    }
+}
+
+/// Cosine similarity of every pair of documents.
+pub fn calculate_all_similarities(
+    documents: &[Document],
+    weighting: Weighting,
+) -> HashMap<String, HashMap<String, f32>> {
+    calculate_all_scores(documents, Scoring::Cosine(weighting))
+}
+
+/// BM25 score of every document for the terms of every other document.
+pub fn calculate_bm25_scores(
+    documents: &[Document],
+    k1: f32,
+    b: f32,
+) -> HashMap<String, HashMap<String, f32>> {
+    calculate_all_scores(documents, Scoring::Bm25 { k1, b })
+}
+
+/// Terms of every document, with repetitions.
+fn terms(documents: &[Document]) -> impl Iterator<Item = &str> {
+    documents
+        .iter()
+        .flat_map(|document| document.counter.keys().map(String::as_str))
+}
+
+fn cosine_rows<T: Send>(
+    documents: &[Document],
+    weighting: Weighting,
+    reduce: impl Fn(usize, &[Match]) -> T + Sync,
+) -> Vec<T> {
+    let vocabulary = Vocabulary::new(terms(documents));
+    let vectors = weigh(documents, weighting)
+        .into_iter()
+        .map(|weights| vocabulary.vector(weights))
+        .collect::<Vec<SparseVector>>();
+    let norms = vectors
+        .iter()
+        .map(|vector| {
+            vector
+                .iter()
+                .map(|(_, x)| *x as f64 * *x as f64)
+                .sum::<f64>()
+                .sqrt()
+        })
+        .collect::<Vec<f64>>();
+
+    let index = InvertedIndex::new(&vectors);
+    index.accumulate(&vectors, documents.len(), |query, sums| {
+        let row = sums
+            .iter()
+            .map(|(document, dot)| {
+                // Divide in document order, so both directions of a pair agree.
+                let (first, second) = (query.min(*document), query.max(*document));
+                let similarity = dot / norms[first] / norms[second];
+                Match {
+                    document: *document,
+                    score: (similarity as f32).clamp(0.0, 1.0),
+                }
+            })
+            .collect::<Vec<Match>>();
+        reduce(query, &row)
+    })
+}
+
+/// Query terms are repeated as many times as they occur in the querying document.
+fn bm25_rows<T: Send>(
+    documents: &[Document],
+    k1: f32,
+    b: f32,
+    reduce: impl Fn(usize, &[Match]) -> T + Sync,
+) -> Vec<T> {
+    let vocabulary = Vocabulary::new(terms(documents));
+    let queries = documents
+        .iter()
+        .map(|document| {
+            let counts = document.counter.iter();
+            vocabulary.vector(counts.map(|(term, count)| (term.as_str(), *count as f32)))
+        })
+        .collect::<Vec<SparseVector>>();
+
+    let n = documents.len() as f32;
+    let mut frequencies = vec![0u32; vocabulary.len()];
+    for query in &queries {
+        for (term, _) in query {
+            frequencies[*term as usize] += 1;
+        }
+    }
+    let idf = frequencies
+        .iter()
+        .map(|df| {
+            let df = *df as f32;
+            ((n - df + 0.5) / (df + 0.5) + 1.0).ln()
+        })
+        .collect::<Vec<f32>>();
+
+    let lengths = queries
+        .iter()
+        .map(|query| query.iter().map(|(_, tf)| tf).sum::<f32>())
+        .collect::<Vec<f32>>();
+    let average_length = lengths.iter().sum::<f32>() / n.max(1.0);
+
+    let weights = queries
+        .iter()
+        .zip(&lengths)
+        .map(|(counts, length)| {
+            let normalisation = k1 * (1.0 - b + b * length / average_length);
+            counts
+                .iter()
+                .map(|(term, tf)| {
+                    let saturation = tf * (k1 + 1.0) / (tf + normalisation);
+                    (*term, idf[*term as usize] * saturation)
+                })
+                .collect()
+        })
+        .collect::<Vec<SparseVector>>();
+
+    let index = InvertedIndex::new(&weights);
+    index.accumulate(&queries, documents.len(), |query, sums| {
+        let row = sums
+            .iter()
+            .map(|(document, score)| Match {
+                document: *document,
+                score: *score as f32,
+            })
+            .collect::<Vec<Match>>();
+        reduce(query, &row)
+    })
+}
+
+/// Cosine of the angle between two sparse vectors.
+///
+/// Sums are accumulated in `f64`, so the result doesn't depend on iteration order.
+pub fn calculate_cosine_similarity<K: Eq + Hash>(
+    vector1: &HashMap<K, f32>,
+    vector2: &HashMap<K, f32>,
+) -> f32 {
+    let norm = |vector: &HashMap<K, f32>| -> f64 {
+        vector
+            .values()
+            .map(|x| *x as f64 * *x as f64)
+            .sum::<f64>()
+            .sqrt()
+    };
+
+    let (smaller, larger) = if vector1.len() <= vector2.len() {
+        (vector1, vector2)
+    } else {
+        (vector2, vector1)
+    };
+    let sum: f64 = smaller
+        .iter()
+        .filter_map(|(key, x)| larger.get(key).map(|y| *x as f64 * *y as f64))
+        .sum();
+
+    let similarity = sum / norm(vector1) / norm(vector2);
+    if similarity.is_nan() {
+        0.0
+    } else {
+        (similarity as f32).clamp(0.0, 1.0)
+    }
+}
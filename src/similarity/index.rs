@@ -0,0 +1,120 @@
+//! Inverted index over sparse document vectors, so that a document is only compared
+//! with the documents it shares terms with.
+
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// Sparse vector of `(term id, weight)` pairs.
+pub type SparseVector = Vec<(u32, f32)>;
+
+pub struct InvertedIndex {
+    /// Documents containing each term id, as `(document, weight)` pairs.
+    postings: Vec<Vec<(u32, f32)>>,
+}
+
+impl InvertedIndex {
+    /// Indexes `vectors`, where vector `i` belongs to document `i`.
+    pub fn new(vectors: &[SparseVector]) -> Self {
+        let terms = vectors
+            .iter()
+            .flat_map(|vector| vector.iter().map(|(term, _)| *term as usize + 1))
+            .max()
+            .unwrap_or(0);
+        let mut postings = vec![Vec::new(); terms];
+        for (document, vector) in vectors.iter().enumerate() {
+            for (term, weight) in vector {
+                postings[*term as usize].push((document as u32, *weight));
+            }
+        }
+        InvertedIndex { postings }
+    }
+
+    /// For every query, sums `query weight × posting weight` per document sharing a
+    /// term with it, and passes the non-zero sums to `score`. Queries never match
+    /// their own document.
+    pub fn accumulate<T: Send>(
+        &self,
+        queries: &[SparseVector],
+        documents: usize,
+        score: impl Fn(usize, &[(usize, f64)]) -> T + Sync,
+    ) -> Vec<T> {
+        queries
+            .par_iter()
+            .enumerate()
+            .map_init(
+                || {
+                    (
+                        vec![0.0f64; documents],
+                        vec![false; documents],
+                        Vec::new(),
+                        Vec::new(),
+                    )
+                },
+                |(sums, seen, touched, matches), (query, vector)| {
+                    for (term, query_weight) in vector {
+                        let postings = match self.postings.get(*term as usize) {
+                            Some(postings) => postings,
+                            None => continue,
+                        };
+                        for (document, weight) in postings {
+                            let document = *document as usize;
+                            if document == query {
+                                continue;
+                            }
+                            if !seen[document] {
+                                seen[document] = true;
+                                touched.push(document);
+                            }
+                            sums[document] += *query_weight as f64 * *weight as f64;
+                        }
+                    }
+
+                    matches.clear();
+                    for document in touched.drain(..) {
+                        if sums[document] != 0.0 {
+                            matches.push((document, sums[document]));
+                        }
+                        sums[document] = 0.0;
+                        seen[document] = false;
+                    }
+                    score(query, matches)
+                },
+            )
+            .collect()
+    }
+}
+
+/// Assigns dense ids to terms in lexicographic order, so that sums over the terms
+/// of a vector, such as dot products, add up in the same order on every run.
+pub struct Vocabulary<'a> {
+    ids: HashMap<&'a str, u32>,
+}
+
+impl<'a> Vocabulary<'a> {
+    pub fn new(terms: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut terms = terms.into_iter().collect::<Vec<&str>>();
+        terms.sort_unstable();
+        terms.dedup();
+        let ids = terms
+            .into_iter()
+            .enumerate()
+            .map(|(id, term)| (term, id as u32))
+            .collect();
+        Vocabulary { ids }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Converts `(term, weight)` pairs of known terms into a sparse vector sorted by
+    /// term id.
+    pub fn vector(&self, terms: impl IntoIterator<Item = (&'a str, f32)>) -> SparseVector {
+        let mut vector = terms
+            .into_iter()
+            .map(|(term, weight)| (self.ids[term], weight))
+            .collect::<SparseVector>();
+        vector.sort_unstable_by_key(|(term, _)| *term);
+        vector
+    }
+}
@@ -0,0 +1,282 @@
+//! Morphosyntactic tags of the NKJP/PoliMorf tagset, e.g. `subst:sg:gen:m1`.
+//!
+//! A dictionary tag column may hold several interpretations joined with `+`,
+//! and a single category may list alternative values joined with `.`, as in
+//! `adj:sg:acc.inst:f:pos`. Values are recognised by their name rather than by
+//! their position, so every part of speech is parsed the same way.
+
+use std::fmt;
+use std::str::FromStr;
+
+macro_rules! tag_enum {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident => $tag:literal,)* }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum $name {
+            $($variant,)*
+        }
+
+        impl $name {
+            /// Name of the value in the tagset.
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $($name::$variant => $tag,)*
+                }
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $($tag => Ok($name::$variant),)*
+                    _ => Err(format!("Unknown {} `{}`", stringify!($name), s)),
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+    };
+}
+
+tag_enum!(
+    /// Grammatical class, the first segment of a tag.
+    PartOfSpeech {
+        Noun => "subst",
+        Depreciative => "depr",
+        Numeral => "num",
+        Adjective => "adj",
+        AdAdjectivalAdjective => "adja",
+        PostPrepositionalAdjective => "adjp",
+        PredicativeAdjective => "adjc",
+        Adverb => "adv",
+        PersonalPronoun => "ppron12",
+        ThirdPersonPronoun => "ppron3",
+        ReflexivePronoun => "siebie",
+        Verb => "verb",
+        Gerund => "ger",
+        ActiveParticiple => "pact",
+        PassiveParticiple => "ppas",
+        ContemporaryAdverbialParticiple => "pcon",
+        AnteriorAdverbialParticiple => "pant",
+        Predicative => "pred",
+        Preposition => "prep",
+        Conjunction => "conj",
+        Complementizer => "comp",
+        Particle => "qub",
+        Abbreviation => "brev",
+        BoundWord => "burk",
+        Interjection => "interj",
+    }
+);
+
+tag_enum!(Number {
+    Singular => "sg",
+    Plural => "pl",
+});
+
+tag_enum!(Case {
+    Nominative => "nom",
+    Genitive => "gen",
+    Dative => "dat",
+    Accusative => "acc",
+    Instrumental => "inst",
+    Locative => "loc",
+    Vocative => "voc",
+});
+
+tag_enum!(Gender {
+    HumanMasculine => "m1",
+    AnimateMasculine => "m2",
+    InanimateMasculine => "m3",
+    Feminine => "f",
+    Neuter => "n",
+    Neuter1 => "n1",
+    Neuter2 => "n2",
+    Plurale1 => "p1",
+    Plurale2 => "p2",
+    Plurale3 => "p3",
+});
+
+tag_enum!(Person {
+    First => "pri",
+    Second => "sec",
+    Third => "ter",
+});
+
+tag_enum!(Aspect {
+    Imperfective => "imperf",
+    Perfective => "perf",
+});
+
+tag_enum!(Degree {
+    Positive => "pos",
+    Comparative => "com",
+    Superlative => "sup",
+});
+
+tag_enum!(Negation {
+    Affirmative => "aff",
+    Negated => "neg",
+});
+
+/// Category of a segment of a tag after the part of speech.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    Number,
+    Case,
+    Gender,
+    Person,
+    Aspect,
+    Degree,
+    Negation,
+    /// A segment outside of the categories above, stored in [`Tag::other`].
+    Other,
+}
+
+/// One interpretation of a word form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    /// `None` for a class outside of the known tagset.
+    pub pos: Option<PartOfSpeech>,
+    pub number: Vec<Number>,
+    pub case: Vec<Case>,
+    pub gender: Vec<Gender>,
+    pub person: Vec<Person>,
+    pub aspect: Vec<Aspect>,
+    pub degree: Vec<Degree>,
+    pub negation: Vec<Negation>,
+    /// Alternative values of every remaining segment, e.g. verb mood (`fin`,
+    /// `praet`) or `refl.nonrefl`, in order.
+    pub other: Vec<Vec<String>>,
+    /// Category of every segment after the part of speech, in order, so the tag
+    /// is written back as it was parsed.
+    pub layout: Vec<Category>,
+}
+
+/// Values of a segment, if all of them belong to the same category.
+fn parse_values<T: FromStr>(values: &[&str]) -> Option<Vec<T>> {
+    values.iter().map(|value| value.parse().ok()).collect()
+}
+
+impl Tag {
+    /// Parses a single interpretation such as `subst:sg:acc.gen:m1`.
+    ///
+    /// A segment whose values don't all belong to one known category, or to a
+    /// category already seen, is kept in [`Tag::other`].
+    pub fn parse(tag: &str) -> Tag {
+        let mut segments = tag.split(':');
+        let mut result = Tag {
+            pos: segments.next().and_then(|pos| pos.parse().ok()),
+            number: Vec::new(),
+            case: Vec::new(),
+            gender: Vec::new(),
+            person: Vec::new(),
+            aspect: Vec::new(),
+            degree: Vec::new(),
+            negation: Vec::new(),
+            other: Vec::new(),
+            layout: Vec::new(),
+        };
+
+        macro_rules! try_category {
+            ($values:expr, $field:ident, $category:ident) => {
+                if !result.layout.contains(&Category::$category) {
+                    if let Some(values) = parse_values($values) {
+                        result.$field = values;
+                        result.layout.push(Category::$category);
+                        continue;
+                    }
+                }
+            };
+        }
+
+        for segment in segments {
+            let values = segment.split('.').collect::<Vec<&str>>();
+            try_category!(&values, number, Number);
+            try_category!(&values, case, Case);
+            try_category!(&values, gender, Gender);
+            try_category!(&values, person, Person);
+            try_category!(&values, aspect, Aspect);
+            try_category!(&values, degree, Degree);
+            try_category!(&values, negation, Negation);
+            result
+                .other
+                .push(values.iter().map(|value| value.to_string()).collect());
+            result.layout.push(Category::Other);
+        }
+
+        result
+    }
+}
+
+impl fmt::Display for Tag {
+    /// Writes the tag back in tagset notation, with segments in the order of
+    /// [`Tag::layout`]. Categories missing from the layout follow in a fixed
+    /// order, and a class outside of the tagset is written as `ign`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn join<T: fmt::Display>(values: &[T]) -> String {
+            values
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(".")
+        }
+
+        let known = [
+            (Category::Number, join(&self.number)),
+            (Category::Case, join(&self.case)),
+            (Category::Gender, join(&self.gender)),
+            (Category::Person, join(&self.person)),
+            (Category::Aspect, join(&self.aspect)),
+            (Category::Degree, join(&self.degree)),
+            (Category::Negation, join(&self.negation)),
+        ];
+        let value = |category| {
+            known
+                .iter()
+                .find(|(known, _)| *known == category)
+                .map_or("", |(_, value)| value.as_str())
+        };
+
+        f.write_str(self.pos.map_or("ign", PartOfSpeech::as_str))?;
+        let mut other = self.other.iter();
+        for category in &self.layout {
+            match category {
+                Category::Other => match other.next() {
+                    Some(values) => write!(f, ":{}", values.join("."))?,
+                    None => continue,
+                },
+                category => write!(f, ":{}", value(*category))?,
+            }
+        }
+        for (category, value) in &known {
+            if !value.is_empty() && !self.layout.contains(category) {
+                write!(f, ":{}", value)?;
+            }
+        }
+        for values in other {
+            write!(f, ":{}", values.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a whole tag column, splitting alternative interpretations on `+`.
+pub fn parse_tags(tags: &str) -> Vec<Tag> {
+    tags.split('+')
+        .filter(|tag| !tag.is_empty())
+        .map(Tag::parse)
+        .collect()
+}
+
+/// Parts of speech of a tag column, without parsing the other categories.
+pub fn parts_of_speech(tags: &str) -> impl Iterator<Item = PartOfSpeech> + '_ {
+    tags.split('+')
+        .filter_map(|tag| tag.split(':').next()?.parse().ok())
+}
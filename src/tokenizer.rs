@@ -0,0 +1,268 @@
+//! Splits text into words, numbers, URLs, email addresses, identifiers such as
+//! `Node.js` or `COVID-19`, and hyphenated compounds such as `biało-czerwony`.
+//!
+//! Letters and digits of any script make up words, and apostrophes inside a word
+//! keep it whole, as in `Kennedy'ego`. Other characters separate tokens, and so
+//! does a dot between a lowercase letter and a capitalized word without digits or
+//! underscores around, as in `koniec.Początek`, which rather lacks a space than
+//! joins an identifier.
+//!
+//! A token starts a sentence when a line break or one of `.`, `!`, `?` and `…`
+//! separates it from the previous token. So does the first token, unless the text
+//! starts with a space, which carries on a sentence such as that around a link.
+
+use regex::Regex;
+use std::fmt;
+use std::ops::Range;
+
+/// What a token looks like.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// Letters only, possibly joined by apostrophes.
+    Word,
+    /// Digits, possibly joined by `.`, `-` or `_`, as in `3.14` or `2021-01-01`.
+    Number,
+    /// `http://`, `https://`, `ftp://` or `www.` address.
+    Url,
+    Email,
+    /// Letters mixed with digits, or parts joined by `.` or `_`, as in `es6`,
+    /// `COVID-19`, `Node.js` or `snake_case`.
+    Identifier,
+    /// Words joined by hyphens, as in `e-mail` or `polsko-niemiecki`.
+    Compound,
+}
+
+impl TokenKind {
+    /// Whether tokens of this kind are looked up in the dictionary, which numbers,
+    /// URLs and email addresses aren't.
+    pub fn is_word(self) -> bool {
+        matches!(
+            self,
+            TokenKind::Word | TokenKind::Identifier | TokenKind::Compound
+        )
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TokenKind::Word => "word",
+            TokenKind::Number => "number",
+            TokenKind::Url => "url",
+            TokenKind::Email => "email",
+            TokenKind::Identifier => "identifier",
+            TokenKind::Compound => "compound",
+        }
+    }
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A token borrowed from the tokenized text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub text: &'a str,
+    pub kind: TokenKind,
+    /// Byte offsets in the tokenized text.
+    pub bytes: Range<usize>,
+    /// Character offsets in the tokenized text.
+    pub chars: Range<usize>,
+    pub sentence_start: bool,
+}
+
+impl Token<'_> {
+    /// Whether the first letter is a capital, as in `Polska` or `JSON`.
+    pub fn is_capitalized(&self) -> bool {
+        self.text
+            .chars()
+            .find(|c| c.is_alphabetic())
+            .is_some_and(char::is_uppercase)
+    }
+
+    /// Whether the token is capitalized without starting a sentence, which hints
+    /// at a proper noun.
+    pub fn is_capitalized_mid_sentence(&self) -> bool {
+        !self.sentence_start && self.is_capitalized()
+    }
+}
+
+/// Which kinds of tokens are kept whole.
+///
+/// A URL or email address that isn't kept whole is tokenized like any other
+/// text, an identifier is split into runs of letters and runs of digits, and a
+/// compound into the words between its hyphens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    pub urls: bool,
+    pub emails: bool,
+    pub identifiers: bool,
+    pub compounds: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            urls: true,
+            emails: true,
+            identifiers: true,
+            compounds: true,
+        }
+    }
+}
+
+const URL_PATTERN: &str = r#"(?:https?://|ftp://|www\.)[^\s<>"'`]*[^\s<>"'`.,;:!?)\]}]"#;
+const EMAIL_PATTERN: &str = r"[\p{L}\p{N}._%+-]+@[\p{L}\p{N}-]+(?:\.[\p{L}\p{N}-]+)+";
+/// Letters and digits, possibly joined by single apostrophes, hyphens, dots or
+/// underscores.
+const RUN_PATTERN: &str = r"[\p{L}\p{M}\p{N}]+(?:['’\-‐._][\p{L}\p{M}\p{N}]+)*";
+/// Letters or digits of a split identifier.
+const PART_PATTERN: &str = r"\p{L}[\p{L}\p{M}]*(?:['’]\p{L}[\p{L}\p{M}]*)*|\p{N}+";
+
+#[derive(Debug, Clone)]
+pub struct Tokenizer {
+    rules: Rules,
+    pattern: Regex,
+    parts: Regex,
+}
+
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Tokenizer::new(Rules::default())
+    }
+}
+
+impl Tokenizer {
+    pub fn new(rules: Rules) -> Self {
+        let mut alternatives = Vec::new();
+        if rules.urls {
+            alternatives.push(format!("(?P<url>{})", URL_PATTERN));
+        }
+        if rules.emails {
+            alternatives.push(format!("(?P<email>{})", EMAIL_PATTERN));
+        }
+        alternatives.push(RUN_PATTERN.to_string());
+        Tokenizer {
+            rules,
+            pattern: Regex::new(&alternatives.join("|")).unwrap(),
+            parts: Regex::new(PART_PATTERN).unwrap(),
+        }
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// Tokens of `text` in order of appearance.
+    pub fn tokenize<'a>(&self, text: &'a str) -> Vec<Token<'a>> {
+        let mut spans = Vec::new();
+        for captures in self.pattern.captures_iter(text) {
+            let m = captures.get(0).unwrap();
+            if captures.name("url").is_some() {
+                spans.push((m.range(), TokenKind::Url));
+            } else if captures.name("email").is_some() {
+                spans.push((m.range(), TokenKind::Email));
+            } else {
+                self.push_run(&mut spans, m.as_str(), m.start());
+            }
+        }
+
+        // Offsets only grow, so characters are counted once.
+        let (mut byte, mut char) = (0, 0);
+        spans
+            .into_iter()
+            .map(|(bytes, kind)| {
+                let gap = &text[byte..bytes.start];
+                let sentence_start =
+                    (byte == 0 && !gap.starts_with(' ')) || gap.contains(is_sentence_break);
+                char += gap.chars().count();
+                let start = char;
+                char += text[bytes.clone()].chars().count();
+                byte = bytes.end;
+                Token {
+                    text: &text[bytes.clone()],
+                    kind,
+                    bytes,
+                    chars: start..char,
+                    sentence_start,
+                }
+            })
+            .collect()
+    }
+
+    fn push_run(&self, spans: &mut Vec<(Range<usize>, TokenKind)>, run: &str, start: usize) {
+        let mut offset = 0;
+        for (index, _) in run.match_indices('.') {
+            if is_missing_space(run, index) {
+                self.push_part(spans, &run[offset..index], start + offset);
+                offset = index + 1;
+            }
+        }
+        self.push_part(spans, &run[offset..], start + offset);
+    }
+
+    fn push_part(&self, spans: &mut Vec<(Range<usize>, TokenKind)>, run: &str, start: usize) {
+        let kind = classify(run);
+        match kind {
+            TokenKind::Identifier if !self.rules.identifiers => {
+                for part in self.parts.find_iter(run) {
+                    let range = start + part.start()..start + part.end();
+                    spans.push((range, classify(part.as_str())));
+                }
+            }
+            TokenKind::Compound if !self.rules.compounds => {
+                let mut offset = start;
+                for part in run.split(is_hyphen) {
+                    spans.push((offset..offset + part.len(), classify(part)));
+                    offset += part.len() + part_separator_len(run, offset + part.len() - start);
+                }
+            }
+            _ => spans.push((start..start + run.len(), kind)),
+        }
+    }
+}
+
+/// Whether `c` joins the parts of a compound.
+pub fn is_hyphen(c: char) -> bool {
+    c == '-' || c == '‐'
+}
+
+/// Whether `c` between two tokens ends a sentence.
+pub(crate) fn is_sentence_break(c: char) -> bool {
+    matches!(c, '.' | '!' | '?' | '…' | '\n')
+}
+
+/// Whether the dot at `index` of `run` separates two sentences, as in
+/// `koniec.Początek`, rather than parts of an identifier such as `Node.js`,
+/// `ASP.NET` or `v1.Beta`.
+fn is_missing_space(run: &str, index: usize) -> bool {
+    let mut after = run[index + 1..].chars();
+    !run.contains(|c: char| c == '_' || c.is_numeric())
+        && run[..index]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_lowercase)
+        && after.next().is_some_and(char::is_uppercase)
+        && after.next().is_some_and(char::is_lowercase)
+}
+
+/// Length of the hyphen at `index` of `run`, 0 past its end.
+fn part_separator_len(run: &str, index: usize) -> usize {
+    run[index..].chars().next().map_or(0, char::len_utf8)
+}
+
+/// Kind of a run of letters and digits without other separators than single
+/// apostrophes, hyphens, dots or underscores.
+pub fn classify(run: &str) -> TokenKind {
+    let has_letter = run.chars().any(char::is_alphabetic);
+    if !has_letter {
+        TokenKind::Number
+    } else if run.contains(|c: char| c == '.' || c == '_' || c.is_numeric()) {
+        TokenKind::Identifier
+    } else if run.contains(is_hyphen) {
+        TokenKind::Compound
+    } else {
+        TokenKind::Word
+    }
+}
@@ -0,0 +1,178 @@
+use lemmatizer::document::{self, Options};
+use lemmatizer::{Casing, Disambiguation, Lemmatizer, Tokenizer};
+
+mod common;
+
+use common::{lemmas, pairs};
+
+const ENTRIES: &[(&str, &str, &str)] = &[
+    ("Polska", "Polska", "subst:sg:nom:f"),
+    ("Polsce", "Polska", "subst:sg:loc:f"),
+    ("polska", "polski", "adj:sg:nom:f:pos"),
+    ("polskiej", "polski", "adj:sg:gen:f:pos"),
+    (
+        "Polski",
+        "Polska",
+        "subst:pl:acc:f+subst:pl:nom:f+subst:pl:voc:f+subst:sg:gen:f",
+    ),
+    ("Polski", "Polski", "subst:sg:nom:m1+subst:sg:voc:m1"),
+    (
+        "polski",
+        "polski",
+        "adj:sg:acc:m3:pos+adj:sg:nom:m1.m2.m3:pos",
+    ),
+    ("rząd", "rząd", "subst:sg:acc:m3+subst:sg:nom:m3"),
+    ("do", "do", "prep:gen"),
+    ("Java", "Java", "subst:sg:nom:f"),
+    ("Javie", "Java", "subst:sg:loc:f"),
+    ("jawa", "jawa", "subst:sg:nom:f"),
+    ("jawie", "jawa", "subst:sg:loc:f"),
+    ("Kot", "Kot", "subst:sg:nom:m1"),
+    ("kot", "kot", "subst:sg:nom:m2"),
+    ("flaga", "flaga", "subst:sg:nom:f"),
+    ("lubi", "lubić", "verb:fin:sg:ter:imperf:nonrefl"),
+];
+
+fn lemmatizer(casing: Casing) -> Lemmatizer {
+    common::lemmatizer(ENTRIES, &["w", "i", "a"]).with_casing(casing)
+}
+
+fn capitalized_mid_sentence(text: &str) -> Vec<&str> {
+    Tokenizer::default()
+        .tokenize(text)
+        .into_iter()
+        .filter(|token| token.is_capitalized_mid_sentence())
+        .map(|token| token.text)
+        .collect()
+}
+
+#[test]
+fn sentence_starts() {
+    assert_eq!(
+        capitalized_mid_sentence("Kot lubi Polskę. Ala też! Czy Java? Tak… Jan\nOla i NATO."),
+        ["Polskę", "Java", "NATO"]
+    );
+    assert_eq!(
+        capitalized_mid_sentence("Wiem, że Kot to nazwisko: „Kot” i (Jan)."),
+        ["Kot", "Kot", "Jan"]
+    );
+    assert_eq!(capitalized_mid_sentence(" Kot lubi"), ["Kot"]);
+    assert!(capitalized_mid_sentence("„Kot” lubi").is_empty());
+}
+
+#[test]
+fn markdown_blocks_start_sentences() {
+    let body = "# Polska\n\nPolska flaga\n\n- Java\n- Javie\n\n| Kot |\n|-----|\n| Java |\n";
+    let text = document::clean_up(body, &Options::default());
+    assert!(capitalized_mid_sentence(&text).is_empty());
+}
+
+#[test]
+fn links_carry_on_sentences() {
+    let body = "Wczoraj pan [Kot](/kot) mówi.\n\n[Java](/java) i [Kot](/kot)\n\n# O [Javie](/java)";
+    let text = document::clean_up(body, &Options::default());
+    assert_eq!(capitalized_mid_sentence(&text), ["Kot", "Kot", "Javie"]);
+
+    let lemmatizer = lemmatizer(Casing::Lookup);
+    let forms = Tokenizer::default()
+        .tokenize(&text)
+        .iter()
+        .map(|token| lemmatizer.lookup_form(token))
+        .collect::<Vec<_>>();
+    assert_eq!(forms.iter().filter(|form| *form == "Kot").count(), 2);
+}
+
+#[test]
+fn ignores_case_by_default() {
+    let lemmatizer = lemmatizer(Casing::default());
+    assert_eq!(
+        lemmas(
+            &lemmatizer,
+            "Mieszkam w Polsce, a polska flaga wisi w Javie."
+        ),
+        pairs(&[
+            ("flaga", 1),
+            ("javie", 1),
+            ("mieszkam", 1),
+            ("polsce", 1),
+            ("polski", 1),
+            ("wisi", 1),
+        ])
+    );
+}
+
+#[test]
+fn looks_up_capitalized_words_as_written() {
+    let lemmatizer = lemmatizer(Casing::Lookup);
+    let token = |text| Tokenizer::default().tokenize(text).pop().unwrap();
+    assert_eq!(lemmatizer.lookup_form(&token("w Polsce")), "Polsce");
+    assert_eq!(lemmatizer.lookup_form(&token("w Javie")), "Javie");
+    assert_eq!(lemmatizer.lookup_form(&token("w jawie")), "jawie");
+    // `Kot` is a surname too, but a sentence starts with it more likely as a cat.
+    assert_eq!(lemmatizer.lookup_form(&token("Kot")), "kot");
+    assert_eq!(lemmatizer.lookup_form(&token("lubi Kot")), "Kot");
+    // Capitals are more often emphasis than a proper noun.
+    assert_eq!(lemmatizer.lookup_form(&token("w POLSKA")), "polska");
+    assert_eq!(lemmatizer.lookup_form(&token("w JAVIE")), "Javie");
+    assert_eq!(lemmatizer.lookup_form(&token("w TypeScript")), "typescript");
+    assert_eq!(lemmatizer.lookup_form(&token("Polsce")), "Polsce");
+}
+
+#[test]
+fn counts_proper_nouns_lowercased() {
+    let lemmatizer = lemmatizer(Casing::Lookup);
+    assert_eq!(
+        lemmas(&lemmatizer, "Kot lubi Polskę, a polska flaga jest w Javie."),
+        pairs(&[
+            ("flaga", 1),
+            ("java", 1),
+            ("jest", 1),
+            ("kot", 1),
+            ("lubić", 1),
+            ("polski", 1),
+            ("polskę", 1),
+        ])
+    );
+}
+
+#[test]
+fn keeps_proper_nouns_apart() {
+    let lemmatizer = lemmatizer(Casing::ProperNouns);
+    let text = "Kot lubi Polskę i TypeScript. W Polsce jest polska flaga i jawa w Javie.";
+    assert_eq!(
+        lemmas(&lemmatizer, text),
+        pairs(&[
+            ("Java", 1),
+            ("Polska", 1),
+            ("Polskę", 1),
+            ("TypeScript", 1),
+            ("flaga", 1),
+            ("jawa", 1),
+            ("jest", 1),
+            ("kot", 1),
+            ("lubić", 1),
+            ("polski", 1),
+        ])
+    );
+    let missing = lemmatizer.count_words(text).missing;
+    assert_eq!(missing.get("TypeScript"), Some(&1));
+    assert_eq!(missing.get("Polskę"), Some(&1));
+}
+
+#[test]
+fn proper_nouns_get_corpus_votes() {
+    let mut lemmatizer =
+        lemmatizer(Casing::ProperNouns).with_disambiguation(Disambiguation::CorpusVotes);
+    let votes = lemmatizer.count_votes("Kot lubi Polskę. W Polsce jest polski rząd.");
+    assert_eq!(votes.get("Polska"), Some(&1));
+    assert_eq!(votes.get("polski"), Some(&1));
+    lemmatizer.set_votes(votes);
+    assert_eq!(
+        lemmas(&lemmatizer, "do Polski"),
+        pairs(&[("Polska", 1), ("do", 1)])
+    );
+    assert_eq!(
+        lemmas(&lemmatizer, "polski rząd"),
+        pairs(&[("polski", 1), ("rząd", 1)])
+    );
+}
@@ -0,0 +1,57 @@
+//! Helpers shared by the integration tests.
+
+#![allow(dead_code)]
+
+use lemmatizer::{Dictionary, Document, Lemmatizer};
+
+/// In-memory dictionary of `(form, lemma, tags)` entries.
+pub fn dictionary(entries: &[(&str, &str, &str)]) -> Dictionary {
+    let mut dictionary = Dictionary::default();
+    for (form, lemma, tags) in entries {
+        dictionary.insert(form, lemma, tags);
+    }
+    dictionary
+}
+
+/// Lemmatizer with an in-memory dictionary of `entries` and `stopwords`.
+pub fn lemmatizer(entries: &[(&str, &str, &str)], stopwords: &[&str]) -> Lemmatizer {
+    let stopwords = stopwords.iter().map(|word| word.to_string()).collect();
+    Lemmatizer::new(dictionary(entries), stopwords)
+}
+
+/// Lemma counts of a text, sorted by lemma.
+pub fn lemmas(lemmatizer: &Lemmatizer, text: &str) -> Vec<(String, u32)> {
+    let mut lemmas = lemmatizer
+        .count_words(text)
+        .lemmas
+        .into_iter()
+        .collect::<Vec<_>>();
+    lemmas.sort_unstable();
+    lemmas
+}
+
+/// Expected lemma counts, comparable with [`lemmas`].
+pub fn pairs(expected: &[(&str, u32)]) -> Vec<(String, u32)> {
+    expected
+        .iter()
+        .map(|(lemma, count)| (lemma.to_string(), *count))
+        .collect()
+}
+
+/// Document with `counts` and no missing words or front matter.
+pub fn document(permalink: &str, counts: &[(&str, u32)]) -> Document {
+    Document {
+        permalink: permalink.to_string(),
+        counter: counts
+            .iter()
+            .map(|(term, count)| (term.to_string(), *count))
+            .collect(),
+        missing: Default::default(),
+        metadata: Default::default(),
+    }
+}
+
+/// Path in the temporary directory that other test processes won't use.
+pub fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("lemmatizer-{}-{}", std::process::id(), name))
+}
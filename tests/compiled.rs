@@ -0,0 +1,104 @@
+use lemmatizer::dictionary::Entry;
+use lemmatizer::Dictionary;
+
+mod common;
+
+const ENTRIES: &[(&str, &str, &str)] = &[
+    ("kot", "kot", "subst:sg:nom:m2"),
+    ("kota", "kot", "subst:sg:gen:m2|subst:sg:acc:m2"),
+    ("kotem", "kot", "subst:sg:inst:m2"),
+    ("Kot", "Kot", "subst:sg:nom:m1"),
+    ("mają", "mieć", "verb:fin:pl:ter:imperf"),
+    ("mają", "maić", "verb:fin:pl:ter:imperf"),
+    ("zażółć", "zażółcić", "verb:impt:sg:sec:perf"),
+    ("i", "i", ""),
+];
+
+fn compile(name: &str) -> std::path::PathBuf {
+    let path = common::temp_path(name);
+    common::dictionary(ENTRIES).compile(&path).unwrap();
+    path
+}
+
+fn forms(dictionary: &Dictionary) -> Vec<(String, Vec<Entry<'_>>)> {
+    let mut forms = Vec::new();
+    dictionary.for_each_form(|form, entries| forms.push((form.to_string(), entries.to_vec())));
+    forms.sort_unstable();
+    forms
+}
+
+/// Offset of a section in a compiled file, from the lengths in its header.
+fn section_start(data: &[u8], section: usize) -> usize {
+    let length = |i: usize| {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&data[8 + i * 8..16 + i * 8]);
+        u64::from_le_bytes(bytes) as usize
+    };
+    8 + 7 * 8 + (0..section).map(length).sum::<usize>()
+}
+
+#[test]
+fn reads_back_what_was_compiled() {
+    let path = compile("round-trip.dict");
+    let compiled = Dictionary::load(&path).unwrap();
+    std::fs::remove_file(path).unwrap();
+    let in_memory = common::dictionary(ENTRIES);
+
+    assert_eq!(compiled.len(), in_memory.len());
+    assert_eq!(compiled.lemma_count(), in_memory.lemma_count());
+    assert_eq!(forms(&compiled), forms(&in_memory));
+    for form in ["kot", "Kot", "kota", "mają", "zażółć", "i", "psa"] {
+        assert_eq!(
+            compiled.candidates(form),
+            in_memory.candidates(form),
+            "{}",
+            form
+        );
+    }
+    for lemma in ["kot", "Kot", "mieć", "maić", "zażółcić", "pies"] {
+        assert_eq!(
+            compiled.paradigm_size(lemma),
+            in_memory.paradigm_size(lemma)
+        );
+    }
+    assert_eq!(compiled.paradigm_size("kot"), 3);
+}
+
+#[test]
+fn rejects_corrupted_files() {
+    let path = compile("corrupted.dict");
+    let data = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let mut truncated = data.clone();
+    truncated.pop();
+    // Candidate lists start with a count, then the lemma id of the first reading.
+    let mut unknown_lemma = data.clone();
+    let first_lemma = section_start(&data, 1) + 4;
+    unknown_lemma[first_lemma..first_lemma + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    let mut too_many_readings = data.clone();
+    let first_count = section_start(&data, 1);
+    too_many_readings[first_count..first_count + 4].copy_from_slice(&1000u32.to_le_bytes());
+    // With two readings, the list of `Kot` runs into that of `i`, whose last bytes,
+    // the tags id 0 of its reading, then read as an empty list. All lists stay
+    // valid, but the form `i` points inside one.
+    let mut misaligned = data.clone();
+    misaligned[first_count..first_count + 4].copy_from_slice(&2u32.to_le_bytes());
+    let mut invalid_utf8 = data.clone();
+    invalid_utf8[section_start(&data, 3)] = 0xff;
+    let mut unsorted_lemmas = data.clone();
+    unsorted_lemmas[section_start(&data, 3)] = b'z';
+
+    for (name, data) in [
+        ("truncated", truncated),
+        ("unknown lemma", unknown_lemma),
+        ("too many readings", too_many_readings),
+        ("misaligned form", misaligned),
+        ("invalid UTF-8", invalid_utf8),
+        ("unsorted lemmas", unsorted_lemmas),
+    ] {
+        std::fs::write(&path, data).unwrap();
+        assert!(Dictionary::load(&path).is_err(), "{}", name);
+    }
+    std::fs::remove_file(path).unwrap();
+}
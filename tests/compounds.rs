@@ -0,0 +1,107 @@
+use lemmatizer::{Compounds, Lemmatizer};
+
+mod common;
+
+use common::pairs;
+
+const ENTRIES: &[(&str, &str, &str)] = &[
+    ("polsko", "polski", "adja"),
+    ("niemieckiej", "niemiecki", "adj:sg:gen:f:pos"),
+    ("kowalskiej", "kowalski", "adj:sg:gen:f:pos"),
+    ("nowak", "nowak", "subst:sg:nom:f"),
+    ("e-mail", "e-mail", "subst:sg:nom:m3"),
+];
+
+fn lemmatizer(compounds: Compounds) -> Lemmatizer {
+    common::lemmatizer(ENTRIES, &[]).with_compounds(compounds)
+}
+
+fn lemmas(compounds: Compounds, text: &str) -> Vec<(String, u32)> {
+    common::lemmas(&lemmatizer(compounds), text)
+}
+
+#[test]
+fn parts_by_default() {
+    let lemmatizer = common::lemmatizer(ENTRIES, &[]);
+    assert_eq!(
+        common::lemmas(&lemmatizer, "polsko-niemieckiej e-mail"),
+        pairs(&[("e-mail", 1), ("niemiecki", 1), ("polski", 1)])
+    );
+    let counts = lemmatizer.count_words("polsko-czeskiej");
+    assert_eq!(counts.lemmas["polski"], 1);
+    assert_eq!(counts.lemmas["czeskiej"], 1);
+    assert_eq!(counts.missing.len(), 1);
+    assert_eq!(counts.missing["polsko-czeskiej"], 1);
+}
+
+#[test]
+fn whole() {
+    assert_eq!(
+        lemmas(Compounds::Whole, "polsko-niemieckiej e-mail"),
+        pairs(&[("e-mail", 1), ("polsko-niemieckiej", 1)])
+    );
+}
+
+#[test]
+fn join() {
+    assert_eq!(
+        lemmas(
+            Compounds::Join,
+            "polsko-niemieckiej Kowalskiej-Nowak e-mail COVID-19"
+        ),
+        pairs(&[
+            ("covid-19", 1),
+            ("e-mail", 1),
+            ("kowalski-nowak", 1),
+            ("polsko-niemiecki", 1),
+        ])
+    );
+}
+
+#[test]
+fn join_and_parts() {
+    assert_eq!(
+        lemmas(Compounds::JoinAndParts, "polsko-niemieckiej e-mail"),
+        pairs(&[
+            ("e-mail", 1),
+            ("niemiecki", 1),
+            ("polski", 1),
+            ("polsko-niemiecki", 1),
+        ])
+    );
+}
+
+#[test]
+fn reports_unknown_parts() {
+    let lemmatizer = lemmatizer(Compounds::Join);
+    let counts = lemmatizer.count_words("polsko-czeskiej polsko-niemieckiej");
+    assert_eq!(counts.lemmas["polsko-czeskiej"], 1);
+    assert_eq!(counts.missing.len(), 1);
+    assert_eq!(counts.missing["polsko-czeskiej"], 1);
+}
+
+#[test]
+fn reports_unknown_compounds_once_with_parts() {
+    let lemmatizer = lemmatizer(Compounds::JoinAndParts);
+    let counts = lemmatizer.count_words("polsko-czeskiej");
+    assert_eq!(counts.lemmas["polski"], 1);
+    assert_eq!(counts.lemmas["czeskiej"], 1);
+    assert_eq!(counts.lemmas["polsko-czeskiej"], 1);
+    assert_eq!(counts.missing.len(), 1);
+    assert_eq!(counts.missing["polsko-czeskiej"], 1);
+}
+
+#[test]
+fn skips_stopword_compounds() {
+    for compounds in [Compounds::Join, Compounds::JoinAndParts] {
+        let lemmatizer =
+            common::lemmatizer(ENTRIES, &["jak-najbardziej", "nowak"]).with_compounds(compounds);
+        assert_eq!(
+            common::lemmas(&lemmatizer, "Jak-najbardziej kowalskiej-nowak"),
+            match compounds {
+                Compounds::JoinAndParts => pairs(&[("kowalski", 1), ("kowalski-nowak", 1)]),
+                _ => pairs(&[("kowalski-nowak", 1)]),
+            }
+        );
+    }
+}
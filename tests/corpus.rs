@@ -0,0 +1,182 @@
+use lemmatizer::document::{FallbackId, Field, Options};
+use lemmatizer::{analyze_path, Document, Lemmatizer};
+use serde_json::json;
+use std::path::PathBuf;
+
+mod common;
+
+const ENTRIES: &[(&str, &str, &str)] = &[
+    ("kot", "kot", "subst"),
+    ("kota", "kot", "subst"),
+    ("kotem", "kot", "subst"),
+    ("mama", "mama", "subst"),
+    ("mamę", "mama", "subst"),
+];
+
+fn lemmatizer() -> Lemmatizer {
+    common::lemmatizer(ENTRIES, &["ma"])
+}
+
+fn path(name: &str) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "tests", "corpus", name]
+        .iter()
+        .collect()
+}
+
+fn analyze(name: &str, fallback_id: FallbackId) -> Document {
+    analyze_path(
+        path(name),
+        &lemmatizer(),
+        &Options {
+            fallback_id,
+            ..Options::default()
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn keeps_permalink_case() {
+    assert_eq!(
+        analyze("yaml.md", FallbackId::default()).permalink,
+        "/TypeScript-Generyki"
+    );
+    assert_eq!(
+        analyze("toml.md", FallbackId::default()).permalink,
+        "/Kot-Mama"
+    );
+    assert_eq!(
+        analyze("quoted.md", FallbackId::default()).permalink,
+        "/Ćwiczenia/Zażółć"
+    );
+}
+
+#[test]
+fn keeps_front_matter_values() {
+    let document = analyze("yaml.md", FallbackId::default());
+    assert_eq!(document.metadata["title"], "Generyki w TypeScripcie");
+    assert_eq!(document.metadata["date"], "2021-01-01");
+    assert_eq!(document.metadata["tags"], json!(["TypeScript", "Generyki"]));
+
+    let document = analyze("toml.md", FallbackId::default());
+    assert_eq!(document.metadata["title"], "Kot i Mama");
+    assert_eq!(document.metadata["date"], "2021-02-03");
+    assert_eq!(document.metadata["draft"], false);
+
+    let document = analyze("quoted.md", FallbackId::default());
+    assert_eq!(document.metadata["title"], "Ćwiczenia: Zażółć Gęślą Jaźń");
+    assert_eq!(document.metadata["description"], "Opis w kilku Liniach.\n");
+}
+
+#[test]
+fn lowercases_body() {
+    let document = analyze("yaml.md", FallbackId::default());
+    assert_eq!(document.counter["kot"], 2);
+    assert_eq!(document.counter["mama"], 2);
+    assert!(document.missing.is_empty());
+
+    let document = analyze("toml.md", FallbackId::default());
+    assert_eq!(document.counter["kot"], 3);
+    assert_eq!(document.counter.len(), 1);
+}
+
+#[test]
+fn falls_back_without_permalink() {
+    assert_eq!(analyze("slug.md", FallbackId::FileSlug).permalink, "slug");
+    assert_eq!(
+        analyze("slug.md", FallbackId::Slug).permalink,
+        "Własny-Slug"
+    );
+    assert_eq!(
+        analyze("slug.md", FallbackId::Path).permalink,
+        path("slug.md").display().to_string()
+    );
+    assert_eq!(
+        analyze("bundle/index.md", FallbackId::Slug).permalink,
+        "bundle"
+    );
+}
+
+#[test]
+fn ignores_permalink_in_body() {
+    let document = analyze("slug.md", FallbackId::FileSlug);
+    assert!(!document.metadata.contains_key("permalink"));
+    assert_eq!(document.counter["permalink"], 1);
+    assert_eq!(document.counter["nie"], 1);
+    assert_eq!(document.counter["ten"], 1);
+}
+
+#[test]
+fn weighs_fields() {
+    let mut options = Options::default();
+    options.weights.set(Field::Title, 3);
+    options.weights.set(Field::Tags, 2);
+    options.weights.set(Field::Body, 4);
+    let document = analyze_path(path("yaml.md"), &lemmatizer(), &options).unwrap();
+    assert_eq!(document.counter["generyki"], 3 + 2);
+    assert_eq!(document.counter["typescript"], 2);
+    assert_eq!(document.counter["kot"], 2 * 4);
+    assert_eq!(document.missing["generyki"], 2);
+}
+
+#[test]
+fn analyzes_body_after_rules_and_tables() {
+    let document = analyze("rules.md", FallbackId::default());
+    assert_eq!(document.permalink, "/Linie-I-Tabele");
+    assert_eq!(document.metadata.len(), 2);
+    assert_eq!(document.counter["kot"], 3);
+    assert_eq!(document.counter["mama"], 2);
+    for word in ["linii", "końcu", "mruczek", "zwierzę", "nagłówek"] {
+        assert!(document.missing.contains_key(word), "{}", word);
+    }
+}
+
+#[test]
+fn front_matter_only_at_start() {
+    let document = analyze("rule-first.md", FallbackId::default());
+    assert_eq!(document.permalink, "rule-first");
+    assert!(document.metadata.is_empty());
+    assert_eq!(document.counter["kot"], 1);
+}
+
+#[test]
+fn rejects_invalid_yaml() {
+    let error = analyze_path(path("invalid-yaml.md"), &lemmatizer(), &Options::default());
+    assert!(error.is_err());
+}
+
+#[test]
+fn skips_posts_with_invalid_front_matter() {
+    let dictionary = common::temp_path("corpus.dict");
+    common::dictionary(ENTRIES).compile(&dictionary).unwrap();
+    let stopwords = common::temp_path("corpus-stopwords.txt");
+    std::fs::write(&stopwords, "ma\n").unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_lemmatizer"))
+        .arg("analyze")
+        .args([path("yaml.md"), path("invalid-yaml.md"), path("rules.md")])
+        .arg("--dictionary")
+        .arg(&dictionary)
+        .arg("--stopwords")
+        .arg(&stopwords)
+        .output()
+        .unwrap();
+    std::fs::remove_file(dictionary).unwrap();
+    std::fs::remove_file(stopwords).unwrap();
+
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Skipping") && stderr.contains("invalid-yaml.md"));
+    let counters: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let permalinks = counters.as_object().unwrap().keys().collect::<Vec<_>>();
+    assert_eq!(permalinks, ["/Linie-I-Tabele", "/TypeScript-Generyki"]);
+}
+
+#[test]
+fn text_between_rules_is_body() {
+    let document = analyze("two-rules.md", FallbackId::default());
+    assert_eq!(document.permalink, "two-rules");
+    assert!(document.metadata.is_empty());
+    assert_eq!(document.counter["kot"], 1);
+    assert_eq!(document.counter["mama"], 1);
+}
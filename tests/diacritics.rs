@@ -0,0 +1,55 @@
+use lemmatizer::dictionary::fold;
+use lemmatizer::Lemmatizer;
+
+mod common;
+
+const ENTRIES: &[(&str, &str, &str)] = &[
+    ("żółw", "żółw", "subst:sg:nom:m2"),
+    ("żółwia", "żółw", "subst:sg:gen:m2"),
+    ("więcej", "dużo", "adv:com"),
+    ("laska", "laska", "subst:sg:nom:f"),
+    ("łaska", "łaska", "subst:sg:nom:f"),
+    ("łaski", "łaska", "subst:sg:gen:f"),
+    ("źródło", "źródło", "subst:sg:nom:n"),
+];
+
+fn lemmatizer() -> Lemmatizer {
+    common::lemmatizer(ENTRIES, &[])
+}
+
+#[test]
+fn folds_polish_letters() {
+    assert_eq!(fold("zażółć gęślą jaźń"), "zazolc gesla jazn");
+    assert_eq!(fold("ŁÓDŹ"), "LODZ");
+    assert_eq!(fold("café"), "cafe");
+}
+
+#[test]
+fn looks_up_folded_forms() {
+    let lemmatizer = lemmatizer();
+    assert_eq!(lemmatizer.lemmatize("zolwia"), None);
+
+    let lemmatizer = lemmatizer.with_diacritics_folding();
+    assert_eq!(lemmatizer.lemmatize("zolwia"), Some("żółw"));
+    assert_eq!(lemmatizer.lemmatize("wiecej"), Some("dużo"));
+    assert_eq!(lemmatizer.lemmatize("laska"), Some("laska"));
+    assert!(lemmatizer.count_words("zolw zolwia").missing.is_empty());
+}
+
+#[test]
+fn restores_words() {
+    let lemmatizer = lemmatizer().with_diacritics_folding();
+    assert_eq!(lemmatizer.restore("zrodlo"), ["źródło"]);
+    assert_eq!(lemmatizer.restore("laska"), ["łaska", "laska"]);
+    assert_eq!(lemmatizer.restore("łaska"), ["łaska", "laska"]);
+    assert!(lemmatizer.restore("kot").is_empty());
+}
+
+#[test]
+fn restores_text() {
+    let lemmatizer = lemmatizer().with_diacritics_folding();
+    assert_eq!(
+        lemmatizer.restore_text("Zolw ma WIECEJ zrodel niz laska, a zrodlo."),
+        "Żółw ma WIĘCEJ zrodel niz laska, a źródło."
+    );
+}
@@ -0,0 +1,108 @@
+use lemmatizer::{Casing, Disambiguation, Lemmatizer};
+
+mod common;
+
+/// Readings of the real dictionary, with paradigms big enough that the lemma with
+/// the most entries is the wrong one.
+const ENTRIES: &[(&str, &str, &str)] = &[
+    ("mam", "mama", "subst:pl:gen:f"),
+    ("mam", "mamić", "verb:impt:sg:sec:imperf:refl.nonrefl"),
+    ("mam", "mieć", "verb:fin:sg:pri:imperf:refl.nonrefl"),
+    ("mamię", "mamić", "verb:fin:sg:pri:imperf:refl.nonrefl"),
+    ("mamisz", "mamić", "verb:fin:sg:sec:imperf:refl.nonrefl"),
+    (
+        "mamił",
+        "mamić",
+        "verb:praet:sg:m1.m2.m3:imperf:refl.nonrefl",
+    ),
+    ("ma", "mieć", "verb:fin:sg:ter:imperf:refl.nonrefl"),
+    ("musisz", "musieć", "verb:fin:sg:sec:imperf:nonrefl"),
+    ("musisz", "musić", "verb:fin:sg:sec:imperf:refl.nonrefl"),
+    ("musiał", "musieć", "verb:praet:sg:m1.m2.m3:imperf:nonrefl"),
+    (
+        "musił",
+        "musić",
+        "verb:praet:sg:m1.m2.m3:imperf:refl.nonrefl",
+    ),
+    ("musiła", "musić", "verb:praet:sg:f:imperf:refl.nonrefl"),
+    ("tak", "tak", "adv:pos+qub"),
+    ("tak", "taka", "subst:pl:gen:f"),
+    ("taką", "taka", "subst:sg:inst:f"),
+    ("w", "w", "prep:acc:nwok+prep:loc:nwok"),
+    ("w", "wiek", "brev:pun"),
+    ("wieku", "wiek", "subst:sg:gen:m3"),
+    ("te", "ten", "adj:pl:acc:m2.m3.f.n1.n2.p2.p3:pos"),
+    ("te", "ty", "ppron12:sg:voc:m1.m2.m3.f.n1.n2:sec"),
+    ("ciebie", "ty", "ppron12:sg:gen:m1.m2.m3.f.n1.n2:sec:akc"),
+    ("Ala", "Al", "subst:sg:acc:m1+subst:sg:gen:m1"),
+    ("Ala", "Ala", "subst:sg:nom:f"),
+    ("Alem", "Al", "subst:sg:inst:m1"),
+    ("Alą", "Ala", "subst:sg:inst:f"),
+    (
+        "Polski",
+        "Polska",
+        "subst:pl:acc:f+subst:pl:nom:f+subst:pl:voc:f+subst:sg:gen:f",
+    ),
+    ("Polski", "Polski", "subst:sg:nom:m1+subst:sg:voc:m1"),
+    ("Polską", "Polska", "subst:sg:inst:f"),
+    ("Jana", "Jan", "subst:sg:acc:m1+subst:sg:gen:m1"),
+    ("Jana", "Jana", "subst:sg:nom:f"),
+    ("Janem", "Jan", "subst:sg:inst:m1"),
+    ("do", "do", "prep:gen"),
+];
+
+fn lemmatizer(disambiguation: Disambiguation) -> Lemmatizer {
+    common::lemmatizer(ENTRIES, &[]).with_disambiguation(disambiguation)
+}
+
+#[test]
+fn prefers_likely_readings() {
+    let lemmatizer = lemmatizer(Disambiguation::default());
+    assert_eq!(lemmatizer.lemmatize("mam"), Some("mieć"));
+    assert_eq!(lemmatizer.lemmatize("tak"), Some("tak"));
+    assert_eq!(lemmatizer.lemmatize("w"), Some("w"));
+    assert_eq!(lemmatizer.lemmatize("te"), Some("ten"));
+    // Same tags, so the bigger paradigm still wins without a corpus.
+    assert_eq!(lemmatizer.lemmatize("musisz"), Some("musić"));
+}
+
+#[test]
+fn names_equal_to_the_form_break_ties() {
+    let lemmatizer = lemmatizer(Disambiguation::default()).with_casing(Casing::Lookup);
+    assert_eq!(lemmatizer.lemmatize("Ala"), Some("Ala"));
+    assert_eq!(
+        common::lemmas(&lemmatizer, "Ma go Ala."),
+        common::pairs(&[("ala", 1), ("go", 1), ("mieć", 1)])
+    );
+}
+
+#[test]
+fn names_equal_to_the_form_dont_outweigh_bigger_paradigms() {
+    let lemmatizer = lemmatizer(Disambiguation::default()).with_casing(Casing::ProperNouns);
+    assert_eq!(lemmatizer.lemmatize("Polski"), Some("Polska"));
+    assert_eq!(lemmatizer.lemmatize("Jana"), Some("Jan"));
+    assert_eq!(
+        common::lemmas(&lemmatizer, "Do Polski, do Jana."),
+        common::pairs(&[("Jan", 1), ("Polska", 1), ("do", 2)])
+    );
+}
+
+#[test]
+fn most_forms() {
+    let lemmatizer = lemmatizer(Disambiguation::MostForms);
+    assert_eq!(lemmatizer.lemmatize("mam"), Some("mamić"));
+    assert_eq!(lemmatizer.lemmatize("tak"), Some("taka"));
+    assert_eq!(lemmatizer.lemmatize("w"), Some("wiek"));
+}
+
+#[test]
+fn corpus_votes_break_ties() {
+    let mut lemmatizer = lemmatizer(Disambiguation::CorpusVotes);
+    let votes = lemmatizer.count_votes("Musiał, bo musisz. W wieku taką.");
+    assert_eq!(votes["musieć"], 1);
+    lemmatizer.set_votes(votes);
+    assert_eq!(lemmatizer.lemmatize("musisz"), Some("musieć"));
+    // Votes don't outweigh the part of speech.
+    assert_eq!(lemmatizer.lemmatize("w"), Some("w"));
+    assert_eq!(lemmatizer.lemmatize("tak"), Some("tak"));
+}
@@ -0,0 +1,63 @@
+use lemmatizer::{Guess, Guesser};
+
+mod common;
+
+const ENTRIES: &[(&str, &str, &str)] = &[
+    ("kotach", "kot", "subst:pl:loc:m2"),
+    ("domach", "dom", "subst:pl:loc:m3"),
+    ("lasach", "las", "subst:pl:loc:m3"),
+    ("rękach", "ręka", "subst:pl:loc:f"),
+    ("żółwia", "żółw", "subst:sg:gen:m2"),
+];
+
+fn guesser() -> Guesser {
+    Guesser::train(&common::dictionary(ENTRIES))
+}
+
+#[test]
+fn learns_the_most_common_rule_of_an_ending() {
+    let guesser = guesser();
+    assert_eq!(
+        guesser.guess("płotach"),
+        Some(Guess {
+            lemma: "płot".to_string(),
+            confidence: 0.75,
+        })
+    );
+}
+
+#[test]
+fn needs_enough_forms_and_a_stem() {
+    let guesser = guesser();
+    assert_eq!(guesser.guess("słonia"), None);
+    assert_eq!(guesser.guess("ach"), None);
+    assert_eq!(guesser.guess("kot"), None);
+}
+
+#[test]
+fn saves_and_loads_rules() {
+    let guesser = guesser();
+    let path = common::temp_path("rules.guesser");
+    guesser.save(&path).unwrap();
+    let saved = std::fs::read_to_string(&path).unwrap();
+    let loaded = Guesser::load(&path).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    let lines = saved.lines().collect::<Vec<&str>>();
+    assert_eq!(lines.len(), guesser.len());
+    assert!(lines.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(lines.contains(&"ach\t3\t\t3\t4"));
+    assert_eq!(loaded.len(), guesser.len());
+    for word in ["płotach", "kotach", "słonia"] {
+        assert_eq!(loaded.guess(word), guesser.guess(word));
+    }
+}
+
+#[test]
+fn rejects_invalid_rules() {
+    let path = common::temp_path("invalid.guesser");
+    std::fs::write(&path, "ach\t3\t\t3\t4\nch\t3\t\ttrzy\t4\n").unwrap();
+    let error = Guesser::load(&path).unwrap_err();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(error.to_string(), "Invalid guesser rule on line 2");
+}
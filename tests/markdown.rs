@@ -0,0 +1,153 @@
+use lemmatizer::document::{clean_up, Field, Options};
+use lemmatizer::Tokenizer;
+
+fn assert_words(body: &str, expected: &[&str]) {
+    assert_words_with(body, expected, &Options::default());
+}
+
+/// Compares words regardless of order, as headings and link texts are collected
+/// apart from the rest of the body.
+fn assert_words_with(body: &str, expected: &[&str], options: &Options) {
+    let text = clean_up(body, options);
+    let mut words = Tokenizer::default()
+        .tokenize(&text)
+        .into_iter()
+        .map(|token| token.text.to_lowercase())
+        .collect::<Vec<String>>();
+    words.sort_unstable();
+    let mut expected = expected.to_vec();
+    expected.sort_unstable();
+    assert_eq!(words, expected, "{:?}", body);
+}
+
+#[test]
+fn paragraphs_and_headings() {
+    assert_words(
+        "# Tytuł\n\nPierwszy akapit.\nDruga linia.\n\n## Śródtytuł",
+        &["tytuł", "pierwszy", "akapit", "druga", "linia", "śródtytuł"],
+    );
+    assert_words("Setext\n======\n\nkoniec", &["setext", "koniec"]);
+}
+
+#[test]
+fn emphasis_keeps_words_whole() {
+    assert_words(
+        "*kursywa*, **pogrubienie**, ~~skreślenie~~ i po**ło**wa",
+        &["kursywa", "pogrubienie", "skreślenie", "i", "połowa"],
+    );
+}
+
+#[test]
+fn fenced_code_blocks() {
+    assert_words(
+        "przed\n\n```ts\nconst kod = 1;\n```\n\npo",
+        &["przed", "po"],
+    );
+    assert_words("przed\n\n```\nbez języka\n```\n\npo", &["przed", "po"]);
+    assert_words("przed\n\n~~~rust\nlet kod;\n~~~\n\npo", &["przed", "po"]);
+    assert_words(
+        "przed\n\n````md\n```\nzagnieżdżony\n```\n````\n\npo",
+        &["przed", "po"],
+    );
+}
+
+#[test]
+fn indented_code_blocks() {
+    assert_words("przed\n\n    wcięty kod\n    dalej\n\npo", &["przed", "po"]);
+}
+
+#[test]
+fn inline_code() {
+    assert_words("użyj `kod` teraz", &["użyj", "teraz"]);
+    assert_words("użyj ``kod z ` w środku`` teraz", &["użyj", "teraz"]);
+    assert_words("słowo`kod`słowo", &["słowo", "słowo"]);
+}
+
+#[test]
+fn links() {
+    assert_words(
+        "zobacz [ten wpis](https://example.com/wpis \"tytuł\") dalej",
+        &["zobacz", "ten", "wpis", "dalej"],
+    );
+    assert_words(
+        "zobacz [ten wpis][ref] i [inny][]\n\n[ref]: https://example.com\n[inny]: /inny",
+        &["zobacz", "ten", "wpis", "i", "inny"],
+    );
+    assert_words("[jeden](/a)[dwa](/b)", &["jeden", "dwa"]);
+    assert_words(
+        "adres <https://example.com> i <jan@example.com>",
+        &["adres", "i"],
+    );
+}
+
+#[test]
+fn images() {
+    assert_words(
+        "obrazek ![kot na płocie](kot.png) i ![pies][ref]\n\n[ref]: pies.png",
+        &["obrazek", "kot", "na", "płocie", "i", "pies"],
+    );
+}
+
+#[test]
+fn link_and_alt_texts_can_be_skipped() {
+    let mut options = Options {
+        alt_text: false,
+        ..Options::default()
+    };
+    options.weights.set(Field::LinkText, 0);
+    assert_words_with(
+        "zobacz [ten wpis](/wpis) i ![kot](kot.png) [![logo](logo.png)](/)",
+        &["zobacz", "i"],
+        &options,
+    );
+
+    let mut options = Options::default();
+    options.weights.set(Field::LinkText, 0);
+    assert_words_with("[link](/) ![obrazek](a.png)", &["obrazek"], &options);
+}
+
+#[test]
+fn html() {
+    assert_words(
+        "tekst <b>pogrubiony</b> tutaj",
+        &["tekst", "pogrubiony", "tutaj"],
+    );
+    assert_words(
+        "przed\n\n<div class=\"uwaga\">\nw bloku\n</div>\n\npo",
+        &["przed", "w", "bloku", "po"],
+    );
+    assert_words("przed\n\n<!-- komentarz -->\n\npo", &["przed", "po"]);
+}
+
+#[test]
+fn lists_and_quotes() {
+    assert_words(
+        "- pierwszy\n- drugi\n  1. zagnieżdżony\n\n> cytat\n> dalej",
+        &["pierwszy", "drugi", "zagnieżdżony", "cytat", "dalej"],
+    );
+    assert_words(
+        "- [x] zrobione\n- [ ] do zrobienia",
+        &["zrobione", "do", "zrobienia"],
+    );
+}
+
+#[test]
+fn tables() {
+    assert_words(
+        "| imię | wiek |\n|------|------|\n| Ala | dużo |",
+        &["imię", "wiek", "ala", "dużo"],
+    );
+}
+
+#[test]
+fn footnotes() {
+    assert_words(
+        "zdanie[^1] dalej\n\n[^1]: przypis",
+        &["zdanie", "dalej", "przypis"],
+    );
+}
+
+#[test]
+fn rules_and_breaks() {
+    assert_words("przed\n\n---\n\npo  \nlinia", &["przed", "po", "linia"]);
+}
@@ -0,0 +1,66 @@
+use lemmatizer::results::{Results, SCHEMA, SCHEMA_VERSION};
+use lemmatizer::similarity::{top_matches, Scoring, Threshold};
+use serde_json::{json, Value};
+
+mod common;
+
+fn results() -> Results {
+    let mut documents = [
+        common::document("/kot", &[("kot", 2), ("dom", 1)]),
+        common::document("/dom", &[("dom", 2)]),
+        common::document("/pies", &[("pies", 1), ("kot", 1)]),
+    ];
+    let metadata = [
+        json!({"title": "Kot", "date": "2021-01-01", "tags": ["zwierzęta"]}),
+        json!({"title": "Dom", "draft": true}),
+        json!({"date": 2021}),
+    ];
+    for (document, metadata) in documents.iter_mut().zip(metadata) {
+        document.metadata = metadata.as_object().unwrap().clone();
+    }
+    let matches = top_matches(&documents, Scoring::default(), 2, Threshold::default());
+    Results::new(&documents, &matches)
+}
+
+#[test]
+fn results_keep_scores_and_front_matter_of_related_posts() {
+    let results = results();
+    assert_eq!(results.version, SCHEMA_VERSION);
+    let value = serde_json::to_value(&results).unwrap();
+    assert_eq!(value["version"], SCHEMA_VERSION);
+
+    let related = &value["documents"]["/kot"]["related"];
+    assert_eq!(related.as_array().unwrap().len(), 2);
+    assert_eq!(related[0]["permalink"], "/pies");
+    assert!(related[0]["score"].as_f64().unwrap() > related[1]["score"].as_f64().unwrap());
+    assert_eq!(related[0]["title"], Value::Null);
+    assert_eq!(related[0]["date"], "2021");
+    assert_eq!(related[0]["metadata"], json!({"date": 2021}));
+    assert_eq!(related[1]["permalink"], "/dom");
+    assert_eq!(related[1]["title"], "Dom");
+    assert_eq!(related[1]["date"], Value::Null);
+    assert_eq!(related[1]["metadata"]["draft"], true);
+    assert_eq!(serde_json::from_value::<Results>(value).unwrap(), results);
+}
+
+#[test]
+fn schema_requires_the_fields_that_are_written() {
+    let schema = serde_json::from_str::<Value>(SCHEMA).unwrap();
+    assert_eq!(schema["properties"]["version"]["const"], SCHEMA_VERSION);
+
+    let mut required = schema["$defs"]["related"]["required"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|field| field.as_str().unwrap())
+        .collect::<Vec<&str>>();
+    required.sort_unstable();
+
+    let value = serde_json::to_value(results()).unwrap();
+    let related = value["documents"]["/kot"]["related"][0]
+        .as_object()
+        .unwrap();
+    let mut fields = related.keys().map(String::as_str).collect::<Vec<&str>>();
+    fields.sort_unstable();
+    assert_eq!(fields, required);
+}
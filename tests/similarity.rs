@@ -0,0 +1,296 @@
+use lemmatizer::similarity::{
+    calculate_bm25_scores, calculate_cosine_similarity, top_matches, weigh, Match, Scoring,
+    Threshold, Weighting,
+};
+use lemmatizer::Document;
+
+mod common;
+
+use common::document;
+
+fn corpus() -> Vec<Document> {
+    vec![
+        document("/kot", &[("kot", 3), ("mysz", 1), ("dom", 1)]),
+        document("/pies", &[("pies", 2), ("kot", 1), ("dom", 2)]),
+        document("/mysz", &[("mysz", 4), ("ser", 2)]),
+        document("/rust", &[("rust", 5), ("cargo", 2)]),
+        document("/dom", &[("dom", 1), ("ogród", 3), ("pies", 1)]),
+        document("/ser", &[("ser", 1)]),
+    ]
+}
+
+/// Best `k` matches of every document, comparing it with every other one.
+fn brute_force(documents: &[Document], weighting: Weighting, k: usize) -> Vec<Vec<Match>> {
+    let vectors = weigh(documents, weighting);
+    (0..documents.len())
+        .map(|query| {
+            let mut matches = (0..documents.len())
+                .filter(|document| *document != query)
+                .map(|document| Match {
+                    document,
+                    score: calculate_cosine_similarity(&vectors[query], &vectors[document]),
+                })
+                .collect::<Vec<Match>>();
+            matches.sort_unstable_by(|a, b| b.cmp(a));
+            matches.truncate(k);
+            matches
+        })
+        .collect()
+}
+
+fn assert_same_matches(actual: &[Vec<Match>], expected: &[Vec<Match>]) {
+    assert_eq!(actual.len(), expected.len());
+    for (query, (actual, expected)) in actual.iter().zip(expected).enumerate() {
+        let documents = |matches: &[Match]| matches.iter().map(|m| m.document).collect::<Vec<_>>();
+        assert_eq!(documents(actual), documents(expected), "query {}", query);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!(
+                (a.score - e.score).abs() < 1e-6,
+                "query {}: {:?} {:?}",
+                query,
+                a,
+                e
+            );
+        }
+    }
+}
+
+#[test]
+fn top_matches_agree_with_pairwise_cosine() {
+    let documents = corpus();
+    let weightings = [
+        Weighting::Counts,
+        Weighting::TfIdf {
+            sublinear_tf: false,
+            smooth_idf: true,
+        },
+        Weighting::TfIdf {
+            sublinear_tf: true,
+            smooth_idf: false,
+        },
+    ];
+    for weighting in weightings {
+        for k in [1, 3, documents.len()] {
+            let actual = top_matches(
+                &documents,
+                Scoring::Cosine(weighting),
+                k,
+                Threshold::default(),
+            );
+            assert_same_matches(&actual, &brute_force(&documents, weighting, k));
+        }
+    }
+}
+
+#[test]
+fn documents_sharing_no_terms_score_0() {
+    let documents = corpus();
+    let matches = top_matches(&documents, Scoring::default(), 3, Threshold::default());
+    let rust = &matches[3];
+    assert_eq!(rust.len(), 3);
+    assert!(rust.iter().all(|m| m.score == 0.0));
+    assert_eq!(
+        rust.iter().map(|m| m.document).collect::<Vec<_>>(),
+        [0, 1, 2]
+    );
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-6,
+        "{} != {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn tf_idf_variants() {
+    let documents = [
+        document("/a", &[("kot", 4), ("dom", 1), ("i", 1)]),
+        document("/b", &[("kot", 1), ("i", 2)]),
+        document("/c", &[("pies", 1), ("i", 1)]),
+    ];
+    let tf_idf = |sublinear_tf, smooth_idf| {
+        weigh(
+            &documents,
+            Weighting::TfIdf {
+                sublinear_tf,
+                smooth_idf,
+            },
+        )
+    };
+
+    let counts = weigh(&documents, Weighting::Counts);
+    assert_eq!(counts[0]["kot"], 4.0);
+    assert_eq!(counts[1]["i"], 2.0);
+
+    let plain = tf_idf(false, false);
+    assert_close(plain[0]["kot"], 4.0 * 1.5f32.ln());
+    assert_close(plain[0]["dom"], 3f32.ln());
+    // A term in every document carries no information.
+    assert_eq!(plain[1]["i"], 0.0);
+
+    let sublinear = tf_idf(true, false);
+    assert_close(sublinear[0]["kot"], (1.0 + 4f32.ln()) * 1.5f32.ln());
+    assert_close(sublinear[2]["pies"], 3f32.ln());
+
+    let smooth = tf_idf(false, true);
+    assert_close(smooth[0]["kot"], 4.0 * ((4.0f32 / 3.0).ln() + 1.0));
+    assert_close(smooth[1]["i"], 2.0);
+
+    let both = tf_idf(true, true);
+    assert_close(both[1]["i"], 1.0 + 2f32.ln());
+    assert_close(both[0]["dom"], 2f32.ln() + 1.0);
+}
+
+/// BM25 score of `document` for the terms of `query`, straight from the formula.
+fn bm25(documents: &[Document], query: usize, document: usize, k1: f32, b: f32) -> f32 {
+    let n = documents.len() as f32;
+    let length = |d: &Document| d.counter.values().sum::<u32>() as f32;
+    let average_length = documents.iter().map(length).sum::<f32>() / n;
+    let candidate = &documents[document];
+    documents[query]
+        .counter
+        .iter()
+        .filter_map(|(term, query_tf)| {
+            let tf = *candidate.counter.get(term)? as f32;
+            let df = documents
+                .iter()
+                .filter(|d| d.counter.contains_key(term))
+                .count() as f32;
+            let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+            let normalisation = k1 * (1.0 - b + b * length(candidate) / average_length);
+            Some(*query_tf as f32 * idf * tf * (k1 + 1.0) / (tf + normalisation))
+        })
+        .sum()
+}
+
+#[test]
+fn bm25_scores() {
+    let documents = corpus();
+    for (k1, b) in [(1.2, 0.75), (2.0, 0.0), (0.5, 1.0)] {
+        let scores = calculate_bm25_scores(&documents, k1, b);
+        for (query, row) in documents.iter().enumerate() {
+            let row = &scores[&row.permalink];
+            assert_eq!(row.len(), documents.len() - 1);
+            for (document, candidate) in documents.iter().enumerate() {
+                if document != query {
+                    let expected = bm25(&documents, query, document, k1, b);
+                    assert_close(row[&candidate.permalink], expected);
+                }
+            }
+        }
+    }
+
+    // Scores depend on which document is the query.
+    let scores = calculate_bm25_scores(&documents, 1.2, 0.75);
+    assert_ne!(scores["/kot"]["/pies"], scores["/pies"]["/kot"]);
+}
+
+#[test]
+fn bm25_ranks_rare_terms_higher() {
+    let documents = [
+        document("/query", &[("kot", 1), ("jest", 1)]),
+        document("/kot", &[("kot", 1), ("ogon", 1)]),
+        document("/jest", &[("jest", 1), ("dom", 1)]),
+        document("/inny", &[("jest", 1), ("las", 1)]),
+    ];
+    let scores = calculate_bm25_scores(&documents, 1.2, 0.75);
+    let query = &scores["/query"];
+    // `kot` occurs in fewer posts than `jest`.
+    assert!(query["/kot"] > query["/jest"]);
+    assert_eq!(query["/jest"], query["/inny"]);
+}
+
+#[test]
+fn top_matches_are_best_first_and_earlier_first_on_ties() {
+    let documents = [
+        document("/a", &[("kot", 1)]),
+        document("/b", &[("kot", 1), ("dom", 1)]),
+        document("/c", &[("kot", 1)]),
+        document("/d", &[("kot", 1)]),
+        document("/e", &[("dom", 1)]),
+    ];
+    let matches = top_matches(&documents, Scoring::default(), 3, Threshold::default());
+    let ranked = |query: usize| {
+        matches[query]
+            .iter()
+            .map(|m| m.document)
+            .collect::<Vec<usize>>()
+    };
+    // `/a`, `/c` and `/d` are identical, `/b` is less similar to each of them.
+    assert_eq!(ranked(0), [2, 3, 1]);
+    assert_eq!(ranked(3), [0, 2, 1]);
+    // `/e` only shares `dom` with `/b`, the others score 0.
+    assert_eq!(ranked(4), [1, 0, 2]);
+    assert_eq!(matches[3][0].score, 1.0);
+
+    let all = top_matches(&documents, Scoring::default(), 10, Threshold::default());
+    assert!(all.iter().all(|row| row.len() == documents.len() - 1));
+    assert!(all[1].windows(2).all(|pair| pair[0] >= pair[1]));
+    let none = top_matches(&documents, Scoring::default(), 0, Threshold::default());
+    assert!(none.iter().all(Vec::is_empty));
+}
+
+fn scores(matches: &[Match]) -> Vec<f32> {
+    matches.iter().map(|m| m.score).collect()
+}
+
+#[test]
+fn thresholds_drop_weak_matches() {
+    let matches = || {
+        vec![
+            Match {
+                document: 1,
+                score: 0.8,
+            },
+            Match {
+                document: 2,
+                score: 0.4,
+            },
+            Match {
+                document: 3,
+                score: 0.2,
+            },
+            Match {
+                document: 4,
+                score: 0.0,
+            },
+        ]
+    };
+    let retained = |absolute, relative| {
+        let mut matches = matches();
+        Threshold { absolute, relative }.retain(&mut matches);
+        scores(&matches)
+    };
+    assert_eq!(retained(0.0, 0.0), [0.8, 0.4, 0.2, 0.0]);
+    assert_eq!(retained(0.2, 0.0), [0.8, 0.4, 0.2]);
+    assert_eq!(retained(0.0, 0.5), [0.8, 0.4]);
+    // The stricter of both applies.
+    assert_eq!(retained(0.5, 0.25), [0.8]);
+    assert_eq!(retained(0.1, 0.5), [0.8, 0.4]);
+    assert_eq!(retained(0.9, 0.0), Vec::<f32>::new());
+}
+
+#[test]
+fn top_matches_apply_thresholds() {
+    let documents = corpus();
+    let everything = top_matches(&documents, Scoring::default(), 5, Threshold::default());
+    let threshold = Threshold {
+        absolute: 0.1,
+        relative: 0.5,
+    };
+    let pruned = top_matches(&documents, Scoring::default(), 5, threshold);
+    for (all, pruned) in everything.iter().zip(&pruned) {
+        let best = all.first().map_or(0.0, |m| m.score);
+        let expected = all
+            .iter()
+            .filter(|m| m.score >= 0.1 && m.score >= best * 0.5)
+            .copied()
+            .collect::<Vec<Match>>();
+        assert_eq!(pruned, &expected);
+    }
+    // `/rust` shares no terms with any other post.
+    assert!(pruned[3].is_empty());
+}
@@ -0,0 +1,26 @@
+use lemmatizer::lemmatizer::build_stopwords;
+use std::path::PathBuf;
+
+fn write(name: &str, contents: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("lemmatizer-{}-{}", std::process::id(), name));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn skips_blank_lines() {
+    let path = write("stopwords.txt", b"  Ale\n\n\t\ni\r\n");
+    let stopwords = build_stopwords(&path).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(stopwords.len(), 2);
+    assert!(stopwords.contains("ale"));
+    assert!(stopwords.contains("i"));
+}
+
+#[test]
+fn reports_invalid_utf8() {
+    let path = write("invalid.txt", b"ale\n\xff\xfe\n");
+    let result = build_stopwords(&path);
+    std::fs::remove_file(path).unwrap();
+    assert!(result.is_err());
+}
@@ -0,0 +1,61 @@
+use lemmatizer::tags::{parse_tags, Aspect, Case, Category, Gender, Number, Person};
+use lemmatizer::{PartOfSpeech, Tag};
+
+#[test]
+fn parses_categories() {
+    let tag = Tag::parse("verb:fin:sg:ter:imperf:refl.nonrefl");
+    assert_eq!(tag.pos, Some(PartOfSpeech::Verb));
+    assert_eq!(tag.number, [Number::Singular]);
+    assert_eq!(tag.person, [Person::Third]);
+    assert_eq!(tag.aspect, [Aspect::Imperfective]);
+    assert_eq!(tag.other, [vec!["fin"], vec!["refl", "nonrefl"]]);
+    assert_eq!(
+        tag.layout,
+        [
+            Category::Other,
+            Category::Number,
+            Category::Person,
+            Category::Aspect,
+            Category::Other,
+        ]
+    );
+
+    let tag = Tag::parse("adj:sg:acc.inst:m1.m2:pos");
+    assert_eq!(tag.case, [Case::Accusative, Case::Instrumental]);
+    assert_eq!(
+        tag.gender,
+        [Gender::HumanMasculine, Gender::AnimateMasculine]
+    );
+}
+
+#[test]
+fn prints_tags_back_unchanged() {
+    for tag in [
+        "verb:fin:sg:ter:imperf:refl.nonrefl",
+        "verb:impt:sg:sec:imperf:refl.nonrefl",
+        "verb:praet:sg:m1.m2.m3:imperf:nonrefl",
+        "subst:sg:acc:m1+subst:sg:gen:m1",
+        "adj:pl:acc:m2.m3.f.n1.n2.p2.p3:pos",
+        "ppron12:sg:dat:m1.m2.m3.f.n1.n2:sec:nakc",
+        "ppron3:sg:gen:m1.m2.m3:ter:akc.nakc:praep",
+        "prep:acc:nwok+prep:loc:nwok",
+        "adv:pos+qub",
+        "brev:pun",
+        "adja",
+    ] {
+        let printed = parse_tags(tag)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>()
+            .join("+");
+        assert_eq!(printed, tag);
+    }
+}
+
+#[test]
+fn keeps_repeated_categories_apart() {
+    let tag = Tag::parse("subst:sg:nom:m1:sg");
+    assert_eq!(tag.number, [Number::Singular]);
+    assert_eq!(tag.other, [vec!["sg"]]);
+    assert_eq!(tag.to_string(), "subst:sg:nom:m1:sg");
+}
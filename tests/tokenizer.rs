@@ -0,0 +1,121 @@
+use lemmatizer::tokenizer::{Rules, TokenKind, Tokenizer};
+
+fn tokens(text: &str, rules: Rules) -> Vec<(&str, TokenKind)> {
+    Tokenizer::new(rules)
+        .tokenize(text)
+        .into_iter()
+        .map(|token| (token.text, token.kind))
+        .collect()
+}
+
+#[test]
+fn kinds() {
+    use TokenKind::*;
+    assert_eq!(
+        tokens(
+            "Wyślij e-mail na jan.kowalski@example.com, zobacz https://example.com/a?b=1. \
+             COVID-19, Node.js i snake_case w es6 kosztują 3.14 zł, Kennedy'ego też.",
+            Rules::default()
+        ),
+        [
+            ("Wyślij", Word),
+            ("e-mail", Compound),
+            ("na", Word),
+            ("jan.kowalski@example.com", Email),
+            ("zobacz", Word),
+            ("https://example.com/a?b=1", Url),
+            ("COVID-19", Identifier),
+            ("Node.js", Identifier),
+            ("i", Word),
+            ("snake_case", Identifier),
+            ("w", Word),
+            ("es6", Identifier),
+            ("kosztują", Word),
+            ("3.14", Number),
+            ("zł", Word),
+            ("Kennedy'ego", Word),
+            ("też", Word),
+        ]
+    );
+}
+
+#[test]
+fn offsets() {
+    let text = "Zażółć gęślą, www.jaźń.pl!";
+    let tokens = Tokenizer::default().tokenize(text);
+    let offsets = tokens
+        .iter()
+        .map(|token| (token.bytes.clone(), token.chars.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(offsets, [(0..10, 0..6), (11..19, 7..12), (21..34, 14..25)]);
+    for token in &tokens {
+        assert_eq!(&text[token.bytes.clone()], token.text);
+        let chars = text.chars().skip(token.chars.start);
+        assert_eq!(
+            chars.take(token.chars.len()).collect::<String>(),
+            token.text
+        );
+    }
+}
+
+#[test]
+fn split_rules() {
+    use TokenKind::*;
+    let rules = Rules {
+        urls: false,
+        emails: false,
+        identifiers: false,
+        compounds: false,
+    };
+    assert_eq!(
+        tokens("e-mail COVID-19 Node.js jan@example.com http://a.pl", rules),
+        [
+            ("e", Word),
+            ("mail", Word),
+            ("COVID", Word),
+            ("19", Number),
+            ("Node", Word),
+            ("js", Word),
+            ("jan", Word),
+            ("example", Word),
+            ("com", Word),
+            ("http", Word),
+            ("a", Word),
+            ("pl", Word),
+        ]
+    );
+}
+
+#[test]
+fn separators() {
+    assert_eq!(
+        tokens(
+            "koniec.Początek -- myślnik - i 'cytat' kot’a",
+            Rules::default()
+        )
+        .into_iter()
+        .map(|(text, _)| text)
+        .collect::<Vec<_>>(),
+        ["koniec", "Początek", "myślnik", "i", "cytat", "kot’a"]
+    );
+}
+
+#[test]
+fn dots_join_identifiers_only() {
+    let tokens = Tokenizer::default().tokenize("Koniec.Początek w ASP.NET, Node.js i v1.Beta.");
+    assert_eq!(
+        tokens
+            .iter()
+            .map(|token| (token.text, token.kind, token.sentence_start))
+            .collect::<Vec<_>>(),
+        [
+            ("Koniec", TokenKind::Word, true),
+            ("Początek", TokenKind::Word, true),
+            ("w", TokenKind::Word, false),
+            ("ASP.NET", TokenKind::Identifier, false),
+            ("Node.js", TokenKind::Identifier, false),
+            ("i", TokenKind::Word, false),
+            ("v1.Beta", TokenKind::Identifier, false),
+        ]
+    );
+}
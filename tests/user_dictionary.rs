@@ -0,0 +1,139 @@
+use lemmatizer::dictionary::{Conflict, Entry};
+use lemmatizer::Dictionary;
+
+mod common;
+
+const ENTRIES: &[(&str, &str, &str)] = &[
+    ("mam", "mamić", "verb:fin:sg:pri:imperf"),
+    ("mam", "mama", "subst:pl:gen:f"),
+    ("hooków", "hooka", "subst:pl:gen:f"),
+    ("kot", "kot", "subst:sg:nom:m2"),
+];
+
+/// Main dictionary with the user dictionaries of `files`, as `(name, contents)`,
+/// applied in order, and the conflicts each of them reported.
+fn apply(files: &[(&str, &str)]) -> (Dictionary, Vec<Vec<Conflict>>) {
+    let mut dictionary = common::dictionary(ENTRIES);
+    let conflicts = files
+        .iter()
+        .map(|(name, contents)| {
+            let path = common::temp_path(name);
+            std::fs::write(&path, contents).unwrap();
+            let conflicts = dictionary.apply_user_dictionary(&path);
+            std::fs::remove_file(path).unwrap();
+            conflicts.unwrap()
+        })
+        .collect();
+    (dictionary, conflicts)
+}
+
+fn lemmas(dictionary: &Dictionary, form: &str) -> Vec<String> {
+    dictionary
+        .candidates(form)
+        .iter()
+        .map(|entry| entry.lemma.to_string())
+        .collect()
+}
+
+#[test]
+fn reads_text_dictionaries() {
+    let (dictionary, _) = apply(&[(
+        "user.txt",
+        "# lemma;form[;tags]\nhook;hooków;subst:pl:gen:m3\n\nmieć;mam\nTypeScript;TypeScriptem\nKot;Kot\n",
+    )]);
+    assert_eq!(
+        dictionary.candidates("hooków"),
+        [Entry {
+            lemma: "hook",
+            raw_tags: "subst:pl:gen:m3"
+        }]
+    );
+    assert_eq!(lemmas(&dictionary, "mam"), ["mieć"]);
+    assert_eq!(lemmas(&dictionary, "TypeScriptem"), ["TypeScript"]);
+    // Found when words are lowercased, unless the lowercased form is known.
+    assert_eq!(lemmas(&dictionary, "typescriptem"), ["TypeScript"]);
+    assert_eq!(lemmas(&dictionary, "Kot"), ["Kot"]);
+    assert_eq!(lemmas(&dictionary, "kot"), ["kot"]);
+}
+
+#[test]
+fn reads_csv_dictionaries() {
+    let (dictionary, _) = apply(&[(
+        "user.csv",
+        "form,lemma\nhooków,hook\n# komentarz\nmam, mieć ,verb:fin:sg:pri:imperf\n",
+    )]);
+    assert_eq!(lemmas(&dictionary, "hooków"), ["hook"]);
+    assert_eq!(
+        dictionary.candidates("mam"),
+        [Entry {
+            lemma: "mieć",
+            raw_tags: "verb:fin:sg:pri:imperf"
+        }]
+    );
+    assert!(dictionary.candidates("form").is_empty());
+}
+
+#[test]
+fn header_is_only_skipped_on_the_first_line() {
+    let (dictionary, _) = apply(&[("header.csv", "hooków,hook\nform,lemma\n")]);
+    assert_eq!(lemmas(&dictionary, "hooków"), ["hook"]);
+    assert_eq!(lemmas(&dictionary, "form"), ["lemma"]);
+}
+
+#[test]
+fn reads_toml_dictionaries() {
+    let (dictionary, _) = apply(&[(
+        "user.toml",
+        "\"hooków\" = \"hook\"\nmam = [\"mieć\", \"mama\"]\nReactem = \"React\"\n",
+    )]);
+    assert_eq!(lemmas(&dictionary, "hooków"), ["hook"]);
+    assert_eq!(lemmas(&dictionary, "mam"), ["mama", "mieć"]);
+    assert_eq!(lemmas(&dictionary, "Reactem"), ["React"]);
+}
+
+#[test]
+fn rejects_malformed_lines() {
+    let path = common::temp_path("malformed.csv");
+    std::fs::write(&path, "hooków\n").unwrap();
+    let result = common::dictionary(ENTRIES).apply_user_dictionary(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(result.unwrap_err().to_string().contains("form,lemma"));
+}
+
+#[test]
+fn later_files_take_precedence() {
+    let (dictionary, conflicts) = apply(&[
+        ("first.csv", "hooków,hook\nmam,mieć\n"),
+        ("second.txt", "hak;hooków\n"),
+    ]);
+    assert_eq!(lemmas(&dictionary, "hooków"), ["hak"]);
+    assert_eq!(lemmas(&dictionary, "mam"), ["mieć"]);
+    assert_eq!(
+        conflicts[1],
+        [Conflict {
+            form: "hooków".to_string(),
+            previous: vec!["hook".to_string()],
+            lemmas: vec!["hak".to_string()],
+        }]
+    );
+}
+
+#[test]
+fn reports_changed_lemmas_only() {
+    let (_, conflicts) = apply(&[("conflicts.csv", "mam,mieć\nkot,kot\nhooks,hook\n")]);
+    assert_eq!(
+        conflicts[0],
+        [Conflict {
+            form: "mam".to_string(),
+            previous: vec!["mama".to_string(), "mamić".to_string()],
+            lemmas: vec!["mieć".to_string()],
+        }]
+    );
+}
+
+#[test]
+fn lemmas_without_forms_left_are_not_counted() {
+    let (dictionary, _) = apply(&[("user.txt", "hook;hooków\n")]);
+    // `hooka` had no other form, `hook` replaces it.
+    assert_eq!(dictionary.lemma_count(), 4);
+}